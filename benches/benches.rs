@@ -3,7 +3,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
 use rayon::iter::plumbing::{bridge_unindexed, Folder, UnindexedConsumer, UnindexedProducer};
 use rayon::iter::ParallelIterator;
-use spliter::{ParallelSpliterator, Spliterator};
+use spliter::{ParallelSpliterator, RangeInclusiveSpliter, Spliterator};
 
 /// Enumerates the numbers that reach the given starting point when iterating
 /// the [Collatz] map, by depth-first search over the [graph] of their orbits.
@@ -62,6 +62,89 @@ fn bench_collatz(c: &mut Criterion) {
         })
         .bench_function("parallel", |b| {
             b.iter(|| Collatz::new(black_box(1)).par_split().count())
+        })
+        .bench_function("auto", |b| {
+            b.iter(|| {
+                Collatz::new(black_box(1))
+                    .par_split()
+                    .with_auto_granularity()
+                    .count()
+            })
+        });
+}
+
+/// An artificially expensive computation.
+fn expensive(n: u32) -> u32 {
+    (0..1000).fold(n, |h, i| h.rotate_left(5) ^ i)
+}
+
+/// Benchmarks for expensive items, where splitting often pays off.
+fn bench_expensive(c: &mut Criterion) {
+    c.benchmark_group("Expensive")
+        .sample_size(10)
+        .sampling_mode(SamplingMode::Flat)
+        .bench_function("parallel", |b| {
+            b.iter(|| {
+                Collatz::new(black_box(1))
+                    .par_split()
+                    .limit_split(1 << 16)
+                    .map(expensive)
+                    .max()
+            })
+        })
+        .bench_function("auto", |b| {
+            b.iter(|| {
+                Collatz::new(black_box(1))
+                    .par_split()
+                    .with_auto_granularity()
+                    .limit_split(1 << 16)
+                    .map(expensive)
+                    .max()
+            })
+        });
+}
+
+/// Benchmarks for on-demand splitting, with idle threads (contended) or busy ones (uncontended).
+fn bench_on_demand(c: &mut Criterion) {
+    // Keep all but one thread busy, so that splits mostly go to waste
+    let with_busy_threads = |f: &(dyn Fn() -> usize + Sync)| {
+        let threads = rayon::current_num_threads();
+        rayon::scope(|s| {
+            for _ in 1..threads {
+                s.spawn(|_| {
+                    black_box((0..1u32 << 24).fold(0u32, |h, i| h.rotate_left(5) ^ i));
+                });
+            }
+            black_box(f());
+        })
+    };
+
+    c.benchmark_group("OnDemand")
+        .sample_size(10)
+        .sampling_mode(SamplingMode::Flat)
+        .bench_function("idle threads", |b| {
+            b.iter(|| Collatz::new(black_box(1)).par_split().count())
+        })
+        .bench_function("idle threads, on demand", |b| {
+            b.iter(|| {
+                Collatz::new(black_box(1))
+                    .par_split()
+                    .split_on_demand()
+                    .count()
+            })
+        })
+        .bench_function("busy threads", |b| {
+            b.iter(|| with_busy_threads(&|| Collatz::new(black_box(1)).par_split().count()))
+        })
+        .bench_function("busy threads, on demand", |b| {
+            b.iter(|| {
+                with_busy_threads(&|| {
+                    Collatz::new(black_box(1))
+                        .par_split()
+                        .split_on_demand()
+                        .count()
+                })
+            })
         });
 }
 
@@ -81,7 +164,7 @@ impl CollatzBuf {
     }
 
     fn buffer(&mut self) {
-        if self.next != None {
+        if self.next.is_some() {
             return;
         }
 
@@ -172,5 +255,36 @@ fn bench_collatz_buf(c: &mut Criterion) {
         });
 }
 
-criterion_group!(benches, bench_collatz, bench_collatz_buf);
+/// Benchmarks for fused reductions.
+fn bench_sum(c: &mut Criterion) {
+    c.benchmark_group("Sum")
+        .sample_size(10)
+        .sampling_mode(SamplingMode::Flat)
+        .bench_function("sequential", |b| {
+            b.iter(|| (0..black_box(1u64 << 26)).sum::<u64>())
+        })
+        .bench_function("rayon", |b| {
+            b.iter(|| {
+                RangeInclusiveSpliter::new(0, black_box(1u64 << 26) - 1)
+                    .par_split()
+                    .sum::<u64>()
+            })
+        })
+        .bench_function("fused", |b| {
+            b.iter(|| {
+                RangeInclusiveSpliter::new(0, black_box(1u64 << 26) - 1)
+                    .par_split()
+                    .sum_spliter()
+            })
+        });
+}
+
+criterion_group!(
+    benches,
+    bench_collatz,
+    bench_collatz_buf,
+    bench_expensive,
+    bench_on_demand,
+    bench_sum
+);
 criterion_main!(benches);
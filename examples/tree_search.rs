@@ -0,0 +1,23 @@
+mod util;
+
+use util::cube::PocketCube;
+use util::dfs::{DepthFirstSearch, Node};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn main() {
+    let impossible = PocketCube::impossible();
+    let root = Node::from(PocketCube::solved());
+    let count = AtomicUsize::new(0);
+    let depth = spliter::tree_search(root, |node| node.children().into_iter().collect::<Vec<_>>())
+        .max_depth_by(|node| {
+            assert!(node.cube != impossible);
+            count.fetch_add(1, Ordering::Relaxed);
+            node.depth.into()
+        });
+
+    // Same result as the hand-written search, which goes all the way to God's number
+    let count = count.into_inner();
+    assert_eq!(count, DepthFirstSearch::new(PocketCube::solved()).count());
+    assert_eq!(depth, u32::from(PocketCube::GODS_NUMBER));
+}
@@ -76,6 +76,7 @@ impl PocketCube {
     pub const GODS_NUMBER: u8 = 11;
 
     /// Create a solved cube.
+    #[rustfmt::skip]
     pub fn solved() -> Self {
         Self {
             faces: [
@@ -92,6 +93,7 @@ impl PocketCube {
     }
 
     /// Create an impossible configuration of the cube.
+    #[rustfmt::skip]
     pub fn impossible() -> Self {
         Self {
             faces: [
@@ -108,6 +110,7 @@ impl PocketCube {
     }
 
     /// Perform a single clockwise quarter-turn of a face.
+    #[rustfmt::skip]
     fn quarter_turn(&self, face: Face) -> Self {
         let perm = match face {
             Up => [
@@ -181,6 +184,7 @@ impl PocketCube {
     }
 
     /// Return a new cube with the given face turn applied.
+    #[rustfmt::skip]
     pub fn turn(&self, turn: Turn) -> Self {
         match turn {
             Clockwise(face) => {
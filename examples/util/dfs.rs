@@ -6,13 +6,24 @@ use Face::*;
 use Turn::*;
 
 /// A node in the graph of Rubik's cube states.
-struct Node {
-    cube: PocketCube,
-    depth: u8,
+pub struct Node {
+    pub cube: PocketCube,
+    pub depth: u8,
     last_face: Option<Face>,
 }
 
 impl Node {
+    /// Create a node that's already `depth` moves into the search, so only
+    /// the last few levels are left to explore.
+    #[allow(dead_code)]
+    pub fn at_depth(cube: PocketCube, depth: u8) -> Self {
+        Self {
+            cube,
+            depth,
+            last_face: None,
+        }
+    }
+
     /// Apply a face turn and return the child node.
     fn turn(&self, turn: Turn) -> Self {
         Self {
@@ -23,7 +34,7 @@ impl Node {
     }
 
     /// Get the children of this node in the graph.
-    fn children(&self) -> impl IntoIterator<Item = Self> + '_ {
+    pub fn children(&self) -> impl IntoIterator<Item = Self> + '_ {
         // Optimization 1: All pocket cubes can be solved in 11 moves or less,
         // so there's no need to search deeper than that.
 
@@ -36,7 +47,7 @@ impl Node {
         // we only need to consider turning the other two faces.
 
         (self.depth < PocketCube::GODS_NUMBER)
-            .then(|| [Up, Right, Back])
+            .then_some([Up, Right, Back])
             .into_iter()
             .flatten()
             .filter(|face| self.last_face != Some(*face))
@@ -68,6 +79,12 @@ impl DepthFirstSearch {
         }
     }
 
+    /// Create a new search starting from the given node.
+    #[allow(dead_code)]
+    pub fn from_node(node: Node) -> Self {
+        Self { stack: vec![node] }
+    }
+
     /// Split this traversal in half if possible.
     #[allow(dead_code)]
     pub fn try_split(&mut self) -> Option<Self> {
@@ -0,0 +1,59 @@
+//! Lock-free reductions.
+
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{
+    AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicU16, AtomicU32,
+    AtomicU64, AtomicU8, AtomicUsize,
+};
+
+/// A primitive type with a matching atomic type, so it can be reduced without locking.
+///
+/// Used by [`ParSpliter::reduce_commutative_atomic()`](crate::ParSpliter::reduce_commutative_atomic).
+pub trait AtomicItem: Copy + Send {
+    /// The atomic version of this type.
+    type Atomic: Sync;
+
+    /// Create an atomic holding `value`.
+    fn new_atomic(value: Self) -> Self::Atomic;
+
+    /// Atomically replace the value with `f(value)`, retrying until no other thread interferes.
+    fn update(atomic: &Self::Atomic, f: impl Fn(Self) -> Self);
+
+    /// Take the value out of the atomic.
+    fn into_value(atomic: Self::Atomic) -> Self;
+}
+
+macro_rules! atomic_item {
+    ($($t:ty => $a:ty),*) => {$(
+        impl AtomicItem for $t {
+            type Atomic = $a;
+
+            fn new_atomic(value: Self) -> $a {
+                <$a>::new(value)
+            }
+
+            fn update(atomic: &$a, f: impl Fn(Self) -> Self) {
+                // The closure never fails, so neither does the update
+                let _ = atomic.fetch_update(Relaxed, Relaxed, |value| Some(f(value)));
+            }
+
+            fn into_value(atomic: $a) -> Self {
+                atomic.into_inner()
+            }
+        }
+    )*};
+}
+
+atomic_item! {
+    bool => AtomicBool,
+    i8 => AtomicI8,
+    i16 => AtomicI16,
+    i32 => AtomicI32,
+    i64 => AtomicI64,
+    isize => AtomicIsize,
+    u8 => AtomicU8,
+    u16 => AtomicU16,
+    u32 => AtomicU32,
+    u64 => AtomicU64,
+    usize => AtomicUsize
+}
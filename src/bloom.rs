@@ -0,0 +1,102 @@
+//! Approximate per-branch deduplication.
+
+use crate::Spliterator;
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// The number of bits set for each item.
+const PROBES: u64 = 3;
+
+/// A [Spliterator] that skips items that were probably already seen *within its branch*.
+///
+/// Created by [`ParSpliter::with_branch_bloom()`](crate::ParSpliter::with_branch_bloom).
+#[derive(Clone, Debug)]
+pub struct BranchBloom<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The bits of the bloom filter.
+    words: Vec<u64>,
+}
+
+impl<T> BranchBloom<T> {
+    pub(crate) fn new(iter: T, bits: usize) -> Self {
+        let words = vec![0; bits.div_ceil(64).max(1)];
+        Self { iter, words }
+    }
+
+    /// Add an item to the filter, returning whether it was (probably) already there.
+    fn insert<U: Hash>(&mut self, item: &U) -> bool {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // Derive the probes from a single hash by double hashing
+        let step = hash.rotate_left(32) | 1;
+        let bits = 64 * self.words.len() as u64;
+
+        let mut seen = true;
+        for i in 0..PROBES {
+            let bit = hash.wrapping_add(i.wrapping_mul(step)) % bits;
+            let word = &mut self.words[(bit / 64) as usize];
+            let mask = 1 << (bit % 64);
+            seen &= *word & mask != 0;
+            *word |= mask;
+        }
+        seen
+    }
+}
+
+impl<T> Iterator for BranchBloom<T>
+where
+    T: Iterator,
+    T::Item: Hash,
+{
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            if !self.insert(&item) {
+                return Some(item);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, hi) = self.iter.size_hint();
+        (0, hi)
+    }
+}
+
+impl<T> Spliterator for BranchBloom<T>
+where
+    T: Spliterator,
+    T::Item: Hash,
+{
+    fn split(&mut self) -> Option<Self> {
+        let bits = 64 * self.words.len();
+        self.iter.split().map(|split| Self::new(split, bits))
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_false_negatives() {
+        let items = (0..1000).chain(0..1000).chain((0..1000).rev());
+
+        // Repeats are always caught, and a big enough filter lets every first occurrence through
+        let actual: Vec<_> = BranchBloom::new(items, 1 << 20).collect();
+        assert_eq!(actual, (0..1000).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,90 @@
+//! Spliterators behind trait objects.
+
+use crate::{ParSpliter, ParallelSpliterator, Spliterator};
+
+/// An object-safe version of [Spliterator].
+///
+/// [Spliterator] itself can't be made into a trait object, since [`split()`](Spliterator::split)
+/// returns `Self`.  This trait returns a box instead, and is implemented for every [Spliterator]
+/// that is [Send].  Use [BoxedSpliterator] to store heterogeneous spliterators.
+pub trait DynSpliterator: Iterator {
+    /// Split this iterator in two, if possible, boxing the new half.
+    fn split_boxed<'a>(&mut self) -> Option<BoxedSpliterator<'a, Self::Item>>
+    where
+        Self: 'a;
+
+    /// Forwards to [`Spliterator::max_items_per_branch()`].
+    fn max_items_boxed(&self) -> Option<usize>;
+
+    /// Forwards to [`Spliterator::should_split()`].
+    fn should_split_boxed(&self) -> bool;
+}
+
+impl<T: Spliterator + Send> DynSpliterator for T {
+    fn split_boxed<'a>(&mut self) -> Option<BoxedSpliterator<'a, Self::Item>>
+    where
+        Self: 'a,
+    {
+        self.split()
+            .map(|split| Box::new(split) as BoxedSpliterator<'a, Self::Item>)
+    }
+
+    fn max_items_boxed(&self) -> Option<usize> {
+        self.max_items_per_branch()
+    }
+
+    fn should_split_boxed(&self) -> bool {
+        self.should_split()
+    }
+}
+
+/// A boxed [Spliterator] trait object.
+pub type BoxedSpliterator<'a, T> = Box<dyn DynSpliterator<Item = T> + Send + 'a>;
+
+impl<'a, T: 'a> Spliterator for BoxedSpliterator<'a, T> {
+    fn split(&mut self) -> Option<Self> {
+        (**self).split_boxed()
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        (**self).max_items_boxed()
+    }
+
+    fn should_split(&self) -> bool {
+        (**self).should_split_boxed()
+    }
+}
+
+/// Box a [Spliterator] and parallelize it.
+pub fn par_split_boxed<'a, T>(iter: T) -> ParSpliter<BoxedSpliterator<'a, T::Item>>
+where
+    T: Spliterator + Send + 'a,
+    T::Item: Send,
+{
+    let boxed: BoxedSpliterator<'a, T::Item> = Box::new(iter);
+    boxed.par_split()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{chain_spliterators, RangeInclusiveSpliter, VecSpliter};
+
+    use rayon::iter::ParallelIterator;
+
+    #[test]
+    fn test_par_split_boxed() {
+        assert_eq!(
+            par_split_boxed(RangeInclusiveSpliter::new(0u32, 999)).sum::<u32>(),
+            499_500
+        );
+
+        let a: BoxedSpliterator<'_, u32> = Box::new(RangeInclusiveSpliter::new(0u32, 499));
+        let b: BoxedSpliterator<'_, u32> =
+            Box::new(VecSpliter::from((500..1000).collect::<Vec<_>>()));
+        let mut items: Vec<_> = chain_spliterators(a, b).par_split().collect();
+        items.sort_unstable();
+        assert!(items.into_iter().eq(0..1000));
+    }
+}
@@ -0,0 +1,149 @@
+//! A split budget that refills over time.
+
+use crate::Spliterator;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A token bucket shared by every branch.
+#[derive(Debug)]
+struct Bucket {
+    /// The number of tokens available.
+    tokens: AtomicUsize,
+    /// The maximum number of tokens.
+    capacity: usize,
+    /// The number of tokens added per second.
+    rate: f64,
+    /// When the bucket was created.
+    start: Instant,
+    /// The total number of tokens granted by refills so far.
+    refilled: AtomicUsize,
+}
+
+impl Bucket {
+    fn new(rate: f64, capacity: usize) -> Self {
+        Self {
+            tokens: AtomicUsize::new(capacity),
+            capacity,
+            rate,
+            start: Instant::now(),
+            refilled: AtomicUsize::new(0),
+        }
+    }
+
+    /// Add any tokens that have accrued since the last refill.
+    fn refill(&self) {
+        let due = (self.start.elapsed().as_secs_f64() * self.rate) as usize;
+        let refilled = self.refilled.load(Ordering::Relaxed);
+        if due <= refilled {
+            return;
+        }
+
+        // Only one thread gets to grant each batch of tokens
+        if self
+            .refilled
+            .compare_exchange(refilled, due, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            let grant = due - refilled;
+            let _ = self
+                .tokens
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                    Some(n.saturating_add(grant).min(self.capacity))
+                });
+        }
+    }
+
+    /// Try to take a token.
+    fn take(&self) -> bool {
+        self.refill();
+        self.tokens
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Return an unused token.
+    fn give_back(&self) {
+        // A refill may have topped up the bucket since we took the token
+        let _ = self
+            .tokens
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                Some(n.saturating_add(1).min(self.capacity))
+            });
+    }
+}
+
+/// A [Spliterator] that spends a token from a shared, refilling bucket for every split.
+///
+/// Created by [`ParSpliter::with_split_token_bucket()`](crate::ParSpliter::with_split_token_bucket).
+#[derive(Clone, Debug)]
+pub struct SplitTokenBucket<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The bucket shared by every split.
+    bucket: Arc<Bucket>,
+}
+
+impl<T> SplitTokenBucket<T> {
+    pub(crate) fn new(iter: T, rate: f64, capacity: usize) -> Self {
+        Self {
+            iter,
+            bucket: Arc::new(Bucket::new(rate, capacity)),
+        }
+    }
+}
+
+impl<T: Iterator> Iterator for SplitTokenBucket<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for SplitTokenBucket<T> {
+    fn split(&mut self) -> Option<Self> {
+        if !self.bucket.take() {
+            return None;
+        }
+
+        if let Some(iter) = self.iter.split() {
+            Some(Self {
+                iter,
+                bucket: self.bucket.clone(),
+            })
+        } else {
+            self.bucket.give_back();
+            None
+        }
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_give_back_capacity() {
+        let bucket = Bucket::new(0.0, 2);
+        assert!(bucket.take());
+
+        // Pretend a refill topped the bucket back up in the meantime
+        bucket.tokens.store(2, Ordering::Relaxed);
+        bucket.give_back();
+        assert_eq!(bucket.tokens.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,136 @@
+//! Splitting byte buffers into records.
+
+use crate::Spliterator;
+
+/// A [Spliterator] over the delimited records of a byte slice.
+///
+/// Each item is a record without its trailing delimiter, borrowed directly from the underlying
+/// buffer (e.g. a memory-mapped file).  Splits always happen at a delimiter, so no record is ever
+/// cut in half.
+#[derive(Clone, Copy, Debug)]
+pub struct ByteRecordSpliter<'a> {
+    /// The unconsumed part of the buffer.
+    data: &'a [u8],
+    /// The record delimiter.
+    delim: u8,
+}
+
+impl<'a> ByteRecordSpliter<'a> {
+    /// Create a spliterator over the records of `data` separated by `delim`.
+    pub fn new(data: &'a [u8], delim: u8) -> Self {
+        Self { data, delim }
+    }
+
+    /// Find the delimiter nearest to the midpoint of the buffer.
+    ///
+    /// A delimiter in the last byte is ignored, since splitting there would leave nothing for the
+    /// right-hand side.
+    fn split_point(&self) -> Option<usize> {
+        let end = self.data.len().checked_sub(1)?;
+        let mid = end / 2;
+
+        // Search outwards from the middle, alternating sides, so that we stop as soon as we find
+        // the closest delimiter rather than scanning a whole half
+        let (mut lo, mut hi) = (mid, mid);
+        while lo > 0 || hi < end {
+            if hi < end {
+                if self.data[hi] == self.delim {
+                    return Some(hi);
+                }
+                hi += 1;
+            }
+
+            if lo > 0 {
+                lo -= 1;
+                if self.data[lo] == self.delim {
+                    return Some(lo);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> Iterator for ByteRecordSpliter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let record;
+        if let Some(i) = self.data.iter().position(|&b| b == self.delim) {
+            record = &self.data[..i];
+            self.data = &self.data[(i + 1)..];
+        } else {
+            record = self.data;
+            self.data = &[];
+        }
+        Some(record)
+    }
+}
+
+impl<'a> Spliterator for ByteRecordSpliter<'a> {
+    fn split(&mut self) -> Option<Self> {
+        let i = self.split_point()?;
+        let (left, right) = self.data.split_at(i + 1);
+        self.data = left;
+        Some(Self::new(right, self.delim))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    /// Make a buffer of newline-terminated records of varying lengths.
+    fn records() -> Vec<u8> {
+        (0..10_000)
+            .flat_map(|i: u32| format!("{}\n", i * i).into_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_point() {
+        let data = records();
+        let expected: Vec<_> = ByteRecordSpliter::new(&data, b'\n').collect();
+
+        let mut left = ByteRecordSpliter::new(&data, b'\n');
+        let right = left.split().unwrap();
+        assert_eq!(left.data.last(), Some(&b'\n'));
+
+        let actual: Vec<_> = left.chain(right).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unsplittable() {
+        assert!(ByteRecordSpliter::new(b"", b'\n').split().is_none());
+        assert!(ByteRecordSpliter::new(b"abc", b'\n').split().is_none());
+        assert!(ByteRecordSpliter::new(b"abc\n", b'\n').split().is_none());
+
+        let mut iter = ByteRecordSpliter::new(b"a\nbc", b'\n');
+        let split = iter.split().unwrap();
+        assert_eq!(iter.collect::<Vec<_>>(), [b"a"]);
+        assert_eq!(split.collect::<Vec<_>>(), [b"bc"]);
+    }
+
+    #[test]
+    fn test_par_split() {
+        let data = records();
+
+        let mut expected: Vec<_> = ByteRecordSpliter::new(&data, b'\n').collect();
+        expected.sort_unstable();
+
+        let mut actual: Vec<_> = ByteRecordSpliter::new(&data, b'\n').par_split().collect();
+        actual.sort_unstable();
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,65 @@
+//! Cooperative cancellation.
+
+use crate::Spliterator;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A [Spliterator] that stops as soon as a shared flag is set.
+///
+/// Created by [`ParSpliter::with_cancel()`](crate::ParSpliter::with_cancel).
+#[derive(Debug)]
+pub struct Cancel<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The cancellation flag.
+    flag: Arc<AtomicBool>,
+}
+
+impl<T> Cancel<T> {
+    pub(crate) fn new(iter: T, flag: Arc<AtomicBool>) -> Self {
+        Self { iter, flag }
+    }
+
+    /// Check whether we've been cancelled.
+    fn cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
+
+impl<T: Iterator> Iterator for Cancel<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cancelled() {
+            None
+        } else {
+            self.iter.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, hi) = self.iter.size_hint();
+        (0, hi)
+    }
+}
+
+impl<T: Spliterator> Spliterator for Cancel<T> {
+    fn split(&mut self) -> Option<Self> {
+        if self.cancelled() {
+            return None;
+        }
+
+        self.iter
+            .split()
+            .map(|iter| Self::new(iter, self.flag.clone()))
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
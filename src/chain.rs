@@ -0,0 +1,129 @@
+//! Chaining spliterators together.
+
+use crate::Spliterator;
+
+/// A [Spliterator] that yields the items of one spliterator, then another.
+///
+/// Created by [`chain_spliterators()`].
+#[derive(Clone, Debug)]
+pub struct ChainSpliter<A, B> {
+    /// The first Spliterator, until it runs out.
+    a: Option<A>,
+    /// The second Spliterator.
+    b: Option<B>,
+}
+
+/// Chain two spliterators together.
+///
+/// The first split hands off all of `b` at once.  After that, whichever side is left gets split
+/// normally.
+pub fn chain_spliterators<A, B>(a: A, b: B) -> ChainSpliter<A, B>
+where
+    A: Spliterator,
+    B: Spliterator<Item = A::Item>,
+{
+    ChainSpliter {
+        a: Some(a),
+        b: Some(b),
+    }
+}
+
+impl<A, B> Iterator for ChainSpliter<A, B>
+where
+    A: Iterator,
+    B: Iterator<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(a) = &mut self.a {
+            if let Some(item) = a.next() {
+                return Some(item);
+            }
+            self.a = None;
+        }
+
+        self.b.as_mut()?.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lo, a_hi) = self.a.as_ref().map_or((0, Some(0)), |a| a.size_hint());
+        let (b_lo, b_hi) = self.b.as_ref().map_or((0, Some(0)), |b| b.size_hint());
+        let hi = a_hi.zip(b_hi).and_then(|(a, b)| a.checked_add(b));
+        (a_lo.saturating_add(b_lo), hi)
+    }
+}
+
+impl<A, B> Spliterator for ChainSpliter<A, B>
+where
+    A: Spliterator,
+    B: Spliterator<Item = A::Item>,
+{
+    fn split(&mut self) -> Option<Self> {
+        match (&mut self.a, &mut self.b) {
+            (Some(_), Some(_)) => Some(Self {
+                a: None,
+                b: self.b.take(),
+            }),
+            (Some(a), None) => a.split().map(|a| Self {
+                a: Some(a),
+                b: None,
+            }),
+            (None, Some(b)) => b.split().map(|b| Self {
+                a: None,
+                b: Some(b),
+            }),
+            (None, None) => None,
+        }
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        let a = self.a.as_ref().map_or(Some(0), A::max_items_per_branch)?;
+        let b = self.b.as_ref().map_or(Some(0), B::max_items_per_branch)?;
+        a.checked_add(b)
+    }
+
+    fn should_split(&self) -> bool {
+        match (&self.a, &self.b) {
+            (Some(_), Some(_)) => true,
+            (Some(a), None) => a.should_split(),
+            (None, Some(b)) => b.should_split(),
+            (None, None) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ParallelSpliterator, RangeInclusiveSpliter};
+
+    use rayon::iter::ParallelIterator;
+
+    #[test]
+    fn test_chain_spliterators() {
+        let seq: Vec<_> = chain_spliterators(
+            RangeInclusiveSpliter::new(0u32, 9),
+            RangeInclusiveSpliter::new(100u32, 109),
+        )
+        .collect();
+        assert_eq!(seq, (0..10).chain(100..110).collect::<Vec<_>>());
+
+        let mut chain = chain_spliterators(
+            RangeInclusiveSpliter::new(0u32, 9),
+            RangeInclusiveSpliter::new(100u32, 109),
+        );
+        let b = chain.split().unwrap();
+        assert_eq!(b.collect::<Vec<_>>(), (100..110).collect::<Vec<_>>());
+
+        let mut par: Vec<_> = chain_spliterators(
+            RangeInclusiveSpliter::new(0u32, 999),
+            RangeInclusiveSpliter::new(5000u32, 5999),
+        )
+        .par_split()
+        .collect();
+        par.sort_unstable();
+        assert_eq!(par, (0..1000).chain(5000..6000).collect::<Vec<_>>());
+    }
+}
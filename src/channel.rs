@@ -0,0 +1,103 @@
+//! Consuming channels in parallel.
+
+use crate::Spliterator;
+
+use std::collections::VecDeque;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+
+/// A [Spliterator] over the messages received from a channel.
+///
+/// Each branch buffers the messages that are already available, and splits hand off half of that
+/// buffer.  When a branch runs out, it pulls more messages from the channel, blocking until one
+/// arrives.  It runs out for good once the channel is disconnected and empty.  Splitting never
+/// blocks.
+#[derive(Debug)]
+pub struct ReceiverSpliter<T> {
+    /// The channel, shared by every split.
+    receiver: Arc<Mutex<Receiver<T>>>,
+    /// The messages we've received, but not yielded yet.
+    buffer: VecDeque<T>,
+}
+
+impl<T> ReceiverSpliter<T> {
+    /// Create a spliterator over the messages from `receiver`.
+    pub fn new(receiver: Receiver<T>) -> Self {
+        Self {
+            receiver: Arc::new(Mutex::new(receiver)),
+            buffer: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> Iterator for ReceiverSpliter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.buffer.pop_front() {
+            return Some(item);
+        }
+
+        let receiver = self.receiver.lock().unwrap();
+        let item = receiver.recv().ok()?;
+        self.buffer.extend(receiver.try_iter());
+        Some(item)
+    }
+}
+
+impl<T> Spliterator for ReceiverSpliter<T> {
+    fn split(&mut self) -> Option<Self> {
+        // Grab whatever's available, unless another branch is waiting on the
+        // channel already
+        if let Ok(receiver) = self.receiver.try_lock() {
+            self.buffer.extend(receiver.try_iter());
+        }
+
+        let len = self.buffer.len();
+        if len >= 2 {
+            Some(Self {
+                receiver: self.receiver.clone(),
+                buffer: self.buffer.split_off(len / 2),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_par_split() {
+        const COUNT: usize = 10_000;
+
+        let (tx, rx) = mpsc::channel();
+        let producer = thread::spawn(move || {
+            for i in 0..COUNT {
+                tx.send(i).unwrap();
+                if i % 1000 == 0 {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+        });
+
+        let counts: Vec<_> = (0..COUNT).map(|_| AtomicUsize::new(0)).collect();
+        ReceiverSpliter::new(rx).par_split().for_each(|i| {
+            counts[i].fetch_add(1, Ordering::Relaxed);
+        });
+
+        producer.join().unwrap();
+        assert!(counts.iter().all(|c| c.load(Ordering::Relaxed) == 1));
+    }
+}
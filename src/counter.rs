@@ -0,0 +1,107 @@
+//! Counting consumed items.
+
+use crate::Spliterator;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A [Spliterator] that counts every item it produces into a shared counter.
+///
+/// Created by [`ParSpliter::with_item_counter()`](crate::ParSpliter::with_item_counter).
+#[derive(Debug)]
+pub struct ItemCounter<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The shared counter.
+    counter: Arc<AtomicUsize>,
+}
+
+impl<T> ItemCounter<T> {
+    pub(crate) fn new(iter: T, counter: Arc<AtomicUsize>) -> Self {
+        Self { iter, counter }
+    }
+}
+
+impl<T: Iterator> Iterator for ItemCounter<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next();
+        if item.is_some() {
+            self.counter.fetch_add(1, Ordering::Relaxed);
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for ItemCounter<T> {
+    fn split(&mut self) -> Option<Self> {
+        self.iter
+            .split()
+            .map(|iter| Self::new(iter, self.counter.clone()))
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+/// A [Spliterator] that counts its leaf branches into a shared counter.
+///
+/// Created by [`ParSpliter::with_branch_counter()`](crate::ParSpliter::with_branch_counter).
+#[derive(Debug)]
+pub struct BranchCounter<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The shared counter.
+    counter: Arc<AtomicUsize>,
+}
+
+impl<T> BranchCounter<T> {
+    pub(crate) fn new(iter: T, counter: Arc<AtomicUsize>) -> Self {
+        Self { iter, counter }
+    }
+}
+
+impl<T: Iterator> Iterator for BranchCounter<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for BranchCounter<T> {
+    fn split(&mut self) -> Option<Self> {
+        self.iter
+            .split()
+            .map(|iter| Self::new(iter, self.counter.clone()))
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+impl<T> Drop for BranchCounter<T> {
+    fn drop(&mut self) {
+        // Each Spliterator ends up in exactly one leaf, once it's done splitting
+        self.counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
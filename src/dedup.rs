@@ -0,0 +1,66 @@
+//! Per-branch deduplication.
+
+use crate::Spliterator;
+
+/// A [Spliterator] that drops consecutive duplicate items within each branch.
+///
+/// Created by [`ParSpliter::dedup_branch()`](crate::ParSpliter::dedup_branch).
+#[derive(Clone, Debug)]
+pub struct DedupBranch<T: Iterator> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The first item of the next run, if we've already read it.
+    next: Option<T::Item>,
+}
+
+impl<T: Iterator> DedupBranch<T> {
+    pub(crate) fn new(iter: T) -> Self {
+        Self { iter, next: None }
+    }
+}
+
+impl<T> Iterator for DedupBranch<T>
+where
+    T: Iterator,
+    T::Item: PartialEq,
+{
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.next.take().or_else(|| self.iter.next())?;
+
+        // Skip the rest of the run, remembering the item that ended it
+        self.next = self.iter.by_ref().find(|next| *next != item);
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let next = self.next.is_some() as usize;
+        let (lo, hi) = self.iter.size_hint();
+        // Everything left could be one long run
+        (
+            lo.saturating_add(next).min(1),
+            hi.and_then(|hi| hi.checked_add(next)),
+        )
+    }
+}
+
+impl<T> Spliterator for DedupBranch<T>
+where
+    T: Spliterator,
+    T::Item: PartialEq,
+{
+    fn split(&mut self) -> Option<Self> {
+        self.iter.split().map(Self::new)
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        let pending = self.next.is_some() as usize;
+        self.iter.max_items_per_branch()?.checked_add(pending)
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
@@ -0,0 +1,80 @@
+//! Checking for duplicate items in debug builds.
+
+use crate::Spliterator;
+
+#[cfg(debug_assertions)]
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+#[cfg(debug_assertions)]
+use std::sync::{Arc, Mutex};
+
+/// A [Spliterator] that panics if any item is produced twice, in debug builds.
+///
+/// Created by [`ParSpliter::with_duplicate_check()`](crate::ParSpliter::with_duplicate_check).
+#[derive(Debug)]
+pub struct DuplicateCheck<T: Iterator> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// Every item produced so far, by any branch.
+    #[cfg(debug_assertions)]
+    seen: Arc<Mutex<HashSet<T::Item>>>,
+}
+
+impl<T: Iterator> DuplicateCheck<T> {
+    pub(crate) fn new(iter: T) -> Self {
+        Self {
+            iter,
+            #[cfg(debug_assertions)]
+            seen: Arc::default(),
+        }
+    }
+}
+
+impl<T> Iterator for DuplicateCheck<T>
+where
+    T: Iterator,
+    T::Item: Clone + Debug + Eq + Hash,
+{
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+
+        #[cfg(debug_assertions)]
+        if !self.seen.lock().unwrap().insert(item.clone()) {
+            panic!(
+                "duplicate item {:?}; is split() handing out overlapping work?",
+                item
+            );
+        }
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> Spliterator for DuplicateCheck<T>
+where
+    T: Spliterator,
+    T::Item: Clone + Debug + Eq + Hash,
+{
+    fn split(&mut self) -> Option<Self> {
+        self.iter.split().map(|iter| Self {
+            iter,
+            #[cfg(debug_assertions)]
+            seen: self.seen.clone(),
+        })
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
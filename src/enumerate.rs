@@ -0,0 +1,49 @@
+//! Per-branch enumeration.
+
+use crate::Spliterator;
+
+/// A [Spliterator] that pairs each item with its index *within its branch*.
+///
+/// Created by [`ParSpliter::enumerate_branch()`](crate::ParSpliter::enumerate_branch).
+#[derive(Clone, Debug)]
+pub struct EnumerateBranch<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The index of the next item in this branch.
+    count: usize,
+}
+
+impl<T> EnumerateBranch<T> {
+    pub(crate) fn new(iter: T) -> Self {
+        Self { iter, count: 0 }
+    }
+}
+
+impl<T: Iterator> Iterator for EnumerateBranch<T> {
+    type Item = (usize, T::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let i = self.count;
+        self.count += 1;
+        Some((i, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for EnumerateBranch<T> {
+    fn split(&mut self) -> Option<Self> {
+        self.iter.split().map(Self::new)
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
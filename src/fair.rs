@@ -0,0 +1,129 @@
+//! Fair iteration over several frontiers.
+
+use crate::{ParSpliter, ParallelSpliterator, Spliterator};
+
+/// A [Spliterator] that takes items from several independent frontiers in turn.
+///
+/// Created by [`par_split_fair()`].
+#[derive(Clone, Debug)]
+pub struct FairSpliter<S> {
+    /// The frontiers that aren't exhausted yet.
+    frontiers: Vec<S>,
+    /// The frontier to take the next item from.
+    cursor: usize,
+}
+
+/// Iterate over several frontiers in parallel, fairly.
+///
+/// Within each branch, items are taken from the frontiers round-robin, rather than draining one
+/// frontier before starting on the next.  Splits hand off half of the frontiers at once, and only
+/// split a frontier itself once a branch is down to just one.  This is useful for multi-source
+/// searches, where every source should make progress at about the same rate.  To mix different
+/// kinds of frontiers, use [BoxedSpliterator](crate::BoxedSpliterator)s.
+pub fn par_split_fair<S>(frontiers: Vec<S>) -> ParSpliter<FairSpliter<S>>
+where
+    S: Spliterator + Send,
+    S::Item: Send,
+{
+    FairSpliter {
+        frontiers,
+        cursor: 0,
+    }
+    .par_split()
+}
+
+impl<S: Iterator> Iterator for FairSpliter<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.frontiers.is_empty() {
+            if self.cursor >= self.frontiers.len() {
+                self.cursor = 0;
+            }
+
+            if let Some(item) = self.frontiers[self.cursor].next() {
+                self.cursor += 1;
+                return Some(item);
+            }
+
+            // This frontier is exhausted; the next one slides into its place
+            self.frontiers.swap_remove(self.cursor);
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let mut lo = 0_usize;
+        let mut hi = Some(lo);
+        for iter in &self.frontiers {
+            let (a, b) = iter.size_hint();
+            lo = lo.saturating_add(a);
+            hi = hi.zip(b).and_then(|(hi, b)| hi.checked_add(b));
+        }
+        (lo, hi)
+    }
+}
+
+impl<S: Spliterator> Spliterator for FairSpliter<S> {
+    fn split(&mut self) -> Option<Self> {
+        let len = self.frontiers.len();
+        let frontiers = if len >= 2 {
+            self.frontiers.split_off(len / 2)
+        } else {
+            vec![self.frontiers.first_mut()?.split()?]
+        };
+
+        Some(Self {
+            frontiers,
+            cursor: 0,
+        })
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.frontiers.iter().try_fold(0usize, |n, iter| {
+            n.checked_add(iter.max_items_per_branch()?)
+        })
+    }
+
+    fn should_split(&self) -> bool {
+        match self.frontiers.as_slice() {
+            [] => false,
+            [iter] => iter.should_split(),
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{BoxedSpliterator, RangeInclusiveSpliter, VecSpliter};
+
+    use rayon::iter::ParallelIterator;
+
+    #[test]
+    fn test_round_robin() {
+        let frontiers = vec![0..3, 10..11, 20..24];
+        let items: Vec<_> = FairSpliter {
+            frontiers,
+            cursor: 0,
+        }
+        .collect();
+        assert_eq!(items, [0, 10, 20, 1, 21, 2, 22, 23]);
+    }
+
+    #[test]
+    fn test_par_split_fair() {
+        let frontiers: Vec<BoxedSpliterator<'_, u32>> = vec![
+            Box::new(RangeInclusiveSpliter::new(0u32, 9)),
+            Box::new(VecSpliter::from((10..1000).collect::<Vec<_>>())),
+            Box::new(RangeInclusiveSpliter::new(1000u32, 99_999)),
+        ];
+
+        let mut items: Vec<_> = par_split_fair(frontiers).collect();
+        items.sort_unstable();
+        assert!(items.into_iter().eq(0..100_000));
+    }
+}
@@ -0,0 +1,133 @@
+//! Parallel directory traversal.
+
+use crate::Spliterator;
+
+use std::fs::{self, DirEntry, ReadDir};
+use std::io;
+use std::path::PathBuf;
+
+/// A [Spliterator] that recursively walks a directory tree.
+///
+/// Yields every entry below the root directory (but not the root itself).  Each directory that is
+/// found is added to a stack of directories to visit, and splits hand off half of that stack.
+/// Symbolic links are not followed.
+#[derive(Debug)]
+pub struct WalkDirSpliter {
+    /// The directories we have yet to read.
+    stack: Vec<PathBuf>,
+    /// The directory we're currently reading.
+    current: Option<ReadDir>,
+}
+
+impl WalkDirSpliter {
+    /// Create a spliterator that walks the tree rooted at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            stack: vec![root.into()],
+            current: None,
+        }
+    }
+}
+
+impl Iterator for WalkDirSpliter {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(dir) = &mut self.current {
+                match dir.next() {
+                    Some(Ok(entry)) => {
+                        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                            self.stack.push(entry.path());
+                        }
+                        return Some(Ok(entry));
+                    }
+                    Some(Err(e)) => return Some(Err(e)),
+                    None => self.current = None,
+                }
+            }
+
+            let path = self.stack.pop()?;
+            match fs::read_dir(path) {
+                Ok(dir) => self.current = Some(dir),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl Spliterator for WalkDirSpliter {
+    fn split(&mut self) -> Option<Self> {
+        // If we're in the middle of a directory, we can hand off the whole
+        // stack; otherwise, keep at least one directory for ourselves
+        let len = self.stack.len();
+        let min = if self.current.is_some() { 1 } else { 2 };
+        if len >= min {
+            let stack = self.stack.split_off(len / 2);
+            Some(Self {
+                stack,
+                current: None,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    use std::path::Path;
+
+    /// Make a directory tree to walk.
+    fn make_tree(root: &Path) -> io::Result<()> {
+        for i in 0..4 {
+            for j in 0..4 {
+                let dir = root.join(format!("{}", i)).join(format!("{}", j));
+                fs::create_dir_all(&dir)?;
+                for k in 0..4 {
+                    fs::write(dir.join(format!("{}.txt", k)), "")?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk_dir() -> io::Result<()> {
+        let root = std::env::temp_dir().join(format!("spliter-walk-dir-{}", std::process::id()));
+        make_tree(&root)?;
+
+        let mut expected = WalkDirSpliter::new(&root)
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        expected.sort();
+
+        let mut actual = WalkDirSpliter::new(&root)
+            .par_split()
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        actual.sort();
+
+        fs::remove_dir_all(&root)?;
+
+        // 4 + 4*4 directories, plus 4*4*4 files
+        assert_eq!(expected.len(), 4 + 16 + 64);
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk_dir_error() {
+        let root = std::env::temp_dir().join("spliter-does-not-exist");
+        let results: Vec<_> = WalkDirSpliter::new(root).collect();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+}
@@ -0,0 +1,45 @@
+//! Automatic tuning of the split granularity.
+
+use std::time::{Duration, Instant};
+
+/// Adjusts how many items a branch consumes between split attempts, based on
+/// how long they take.
+#[derive(Debug)]
+pub(crate) struct Tuner {
+    /// The number of items to consume before trying to split again.
+    batch: usize,
+    /// When the current batch started.
+    start: Instant,
+}
+
+impl Tuner {
+    /// How long we'd like each batch to take.
+    const TARGET: Duration = Duration::from_micros(20);
+    /// The largest batch size we'll use.
+    const MAX_BATCH: usize = 1 << 12;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            batch: 1,
+            start: Instant::now(),
+        }
+    }
+
+    /// Start timing a batch, returning its size.
+    pub(crate) fn start(&mut self) -> usize {
+        self.start = Instant::now();
+        self.batch
+    }
+
+    /// Finish timing a batch, and adjust the size of the next one.
+    pub(crate) fn finish(&mut self) {
+        let elapsed = self.start.elapsed();
+        if elapsed < Self::TARGET / 2 {
+            // Items are cheap, so split attempts are relatively expensive
+            self.batch = (2 * self.batch).min(Self::MAX_BATCH);
+        } else if elapsed > 2 * Self::TARGET {
+            // Items are expensive, so try to split more often
+            self.batch = (self.batch / 2).max(1);
+        }
+    }
+}
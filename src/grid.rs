@@ -0,0 +1,144 @@
+//! Splitting two-dimensional regions.
+
+use crate::Spliterator;
+
+use std::ops::Range;
+
+/// A [Spliterator] over the `(x, y)` coordinates of a rectangular grid.
+///
+/// Each branch visits its cells in row-major order.  Splits cut the region in half along its
+/// larger dimension, so branches work on compact, cache-friendly tiles rather than long strips.
+#[derive(Clone, Debug)]
+pub struct GridSpliter {
+    /// The columns of this region.
+    xs: Range<usize>,
+    /// The rows of this region.
+    ys: Range<usize>,
+    /// The next cell.
+    cursor: (usize, usize),
+}
+
+impl GridSpliter {
+    /// Create a spliterator over a `width` × `height` grid.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::region(0..width, 0..height)
+    }
+
+    /// Create a spliterator over a rectangular region.
+    fn region(xs: Range<usize>, ys: Range<usize>) -> Self {
+        let cursor = (xs.start, ys.start);
+        Self { xs, ys, cursor }
+    }
+
+    /// Check whether any items have been consumed.
+    fn untouched(&self) -> bool {
+        self.cursor == (self.xs.start, self.ys.start)
+    }
+
+    /// Split off the rows from `y` onwards.
+    fn split_rows(&mut self, y: usize) -> Self {
+        let split = Self::region(self.xs.clone(), y..self.ys.end);
+        self.ys.end = y;
+        split
+    }
+}
+
+impl Iterator for GridSpliter {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (x, y) = self.cursor;
+        if self.xs.is_empty() || y >= self.ys.end {
+            return None;
+        }
+
+        self.cursor = if x + 1 < self.xs.end {
+            (x + 1, y)
+        } else {
+            (self.xs.start, y + 1)
+        };
+
+        Some((x, y))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (x, y) = self.cursor;
+        let rows = self.ys.end.saturating_sub(y);
+        let len = (rows * self.xs.len()).saturating_sub(x - self.xs.start);
+        (len, Some(len))
+    }
+}
+
+impl Spliterator for GridSpliter {
+    fn split(&mut self) -> Option<Self> {
+        let (width, height) = (self.xs.len(), self.ys.len());
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        if self.untouched() {
+            if width >= height && width >= 2 {
+                let mid = self.xs.start + width / 2;
+                let split = Self::region(mid..self.xs.end, self.ys.clone());
+                self.xs.end = mid;
+                return Some(split);
+            } else if height >= 2 {
+                let mid = self.ys.start + height / 2;
+                return Some(self.split_rows(mid));
+            } else {
+                return None;
+            }
+        }
+
+        // We've started the current row, so we can only hand off some of the
+        // rows after it
+        let rows = self.ys.end.checked_sub(self.cursor.1 + 1)?;
+        if rows > 0 {
+            let mid = self.ys.end - rows.div_ceil(2);
+            Some(self.split_rows(mid))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    /// All the cells of a grid, in row-major order.
+    fn cells(width: usize, height: usize) -> Vec<(usize, usize)> {
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn test_split() {
+        let mut grid = GridSpliter::new(4, 2);
+        let split = grid.split().unwrap();
+        assert_eq!(grid.collect::<Vec<_>>(), [(0, 0), (1, 0), (0, 1), (1, 1)]);
+        assert_eq!(split.collect::<Vec<_>>(), [(2, 0), (3, 0), (2, 1), (3, 1)]);
+
+        let mut grid = GridSpliter::new(3, 4);
+        assert_eq!(grid.next(), Some((0, 0)));
+        let split = grid.split().unwrap();
+        assert_eq!(grid.size_hint(), (5, Some(5)));
+        assert_eq!(split.collect::<Vec<_>>(), cells(3, 4)[6..]);
+
+        assert!(GridSpliter::new(1, 1).split().is_none());
+        assert!(GridSpliter::new(0, 5).split().is_none());
+        assert_eq!(GridSpliter::new(0, 5).count(), 0);
+    }
+
+    #[test]
+    fn test_par_split() {
+        let mut actual: Vec<_> = GridSpliter::new(123, 45).par_split().collect();
+        actual.sort_unstable_by_key(|&(x, y)| (y, x));
+        assert_eq!(actual, cells(123, 45));
+    }
+}
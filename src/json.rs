@@ -0,0 +1,92 @@
+//! Parallel traversal of JSON documents.
+
+use crate::Spliterator;
+
+use serde_json::Value;
+
+/// A [Spliterator] over every node of a parsed JSON document.
+///
+/// Yields the root and every value nested inside it, in depth-first order.  The children of
+/// arrays and objects are pushed onto a stack of nodes to visit, and splits hand off half of that
+/// stack.
+#[derive(Clone, Debug)]
+pub struct JsonSpliter<'a> {
+    /// The nodes we have yet to visit.
+    stack: Vec<&'a Value>,
+}
+
+impl<'a> JsonSpliter<'a> {
+    /// Create a spliterator over the nodes of `root`.
+    pub fn new(root: &'a Value) -> Self {
+        Self { stack: vec![root] }
+    }
+}
+
+impl<'a> Iterator for JsonSpliter<'a> {
+    type Item = &'a Value;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.stack.pop()?;
+        match value {
+            Value::Array(values) => self.stack.extend(values.iter().rev()),
+            Value::Object(map) => self.stack.extend(map.values().rev()),
+            _ => {}
+        }
+        Some(value)
+    }
+}
+
+impl<'a> Spliterator for JsonSpliter<'a> {
+    fn split(&mut self) -> Option<Self> {
+        let len = self.stack.len();
+        if len >= 2 {
+            let stack = self.stack.split_off(len / 2);
+            Some(Self { stack })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    use serde_json::json;
+
+    /// Make a document with some nested arrays and objects.
+    fn document() -> Value {
+        let rows: Vec<_> = (0..100)
+            .map(|i| {
+                json!({
+                    "id": i,
+                    "name": format!("row {}", i),
+                    "tags": ["a", "b", null, [i, i + 1]],
+                    "nested": { "x": i * 2, "y": { "z": true } },
+                })
+            })
+            .collect();
+        json!({ "rows": rows, "count": 100 })
+    }
+
+    #[test]
+    fn test_json_nodes() {
+        let doc = document();
+
+        // Each row has itself, 4 fields, 4 tags, 2 nested tags, and 3 nested values
+        let expected = 3 + 100 * 14;
+        assert_eq!(JsonSpliter::new(&doc).count(), expected);
+
+        let mut addrs: Vec<_> = JsonSpliter::new(&doc)
+            .par_split()
+            .map(|v| v as *const Value as usize)
+            .collect();
+        addrs.sort_unstable();
+        addrs.dedup();
+        assert_eq!(addrs.len(), expected);
+    }
+}
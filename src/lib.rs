@@ -14,59 +14,379 @@
 
 #![deny(missing_docs)]
 
+mod atomic;
+mod bloom;
+mod boxed;
+mod bucket;
+mod bytes;
+mod cancel;
+mod chain;
+mod channel;
+mod counter;
+mod dedup;
+mod duplicates;
+mod enumerate;
+mod fair;
+#[cfg(feature = "fs")]
+mod fs;
+mod granularity;
+mod grid;
+#[cfg(feature = "serde_json")]
+mod json;
+mod limit;
+mod list;
+mod metrics;
+#[cfg(feature = "numa")]
+mod numa;
+mod path;
+mod pause;
+mod prime;
+mod product;
+mod progress;
+mod range;
+mod rate;
+mod rev;
+mod runs;
+mod sample;
+mod schedule;
+#[cfg(feature = "scoped")]
+mod scoped;
+mod slice;
+mod span;
+mod stepper;
+mod trace;
+mod tree;
+mod vec;
+mod while_some;
+mod worker_init;
+mod yield_every;
+
+pub use atomic::AtomicItem;
+pub use bloom::BranchBloom;
+pub use boxed::{par_split_boxed, BoxedSpliterator, DynSpliterator};
+pub use bucket::SplitTokenBucket;
+pub use bytes::ByteRecordSpliter;
+pub use cancel::Cancel;
+pub use chain::{chain_spliterators, ChainSpliter};
+pub use channel::ReceiverSpliter;
+pub use counter::{BranchCounter, ItemCounter};
+pub use dedup::DedupBranch;
+pub use duplicates::DuplicateCheck;
+pub use enumerate::EnumerateBranch;
+pub use fair::{par_split_fair, FairSpliter};
+#[cfg(feature = "fs")]
+pub use fs::WalkDirSpliter;
+pub use grid::GridSpliter;
+#[cfg(feature = "serde_json")]
+pub use json::JsonSpliter;
+pub use limit::Limit;
+pub use list::{LinkedListSpliter, ParallelSplitList};
+pub use metrics::{LiveMetrics, MetricsSink};
+#[cfg(feature = "numa")]
+pub use numa::NumaAffinity;
+pub use path::{BranchId, BranchPath, Side};
+pub use prime::Primed;
+pub use product::ProductSpliter;
+pub use progress::Progress;
+pub use range::RangeInclusiveSpliter;
+pub use rate::RateLimit;
+pub use rev::RevBranch;
+pub use runs::RunLengthSpliter;
+pub use sample::SampleEvery;
+pub use schedule::{Scheduled, SplitSchedule};
+#[cfg(feature = "scoped")]
+pub use scoped::par_split_scoped_threads;
+pub use stepper::{from_stepper, Stepper};
+#[cfg(feature = "trace")]
+pub use trace::{TraceEvent, TraceKind};
+pub use tree::{tree_search, TreeSearch};
+pub use vec::{buffer_all, ParallelSplitDrain, VecDrainSpliter, VecSpliter};
+pub use while_some::WhileSomeBranch;
+pub use worker_init::WorkerInit;
+pub use yield_every::YieldEvery;
+
+use granularity::Tuner;
+use path::BranchLocation;
+use pause::Pausable;
+use slice::SliceFill;
+use span::{BranchSpan, ParentSpan};
+use trace::Tracer;
+
 use rayon::iter::plumbing::{Folder, Reducer, UnindexedConsumer};
-use rayon::iter::ParallelIterator;
-use rayon::{current_num_threads, join_context};
+use rayon::iter::{ParallelExtend, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+use rayon::{current_num_threads, join, join_context};
+
+use std::cmp::{Ordering as CmpOrdering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::iter::{self, Product, Sum};
+use std::ops::{Add, Mul};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{SendError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// An iterator that can be split.
 pub trait Spliterator: Iterator + Sized {
     /// Split this iterator in two, if possible.
     fn split(&mut self) -> Option<Self>;
+
+    /// An upper bound on the number of items this branch will yield, if known.
+    ///
+    /// Branches that report a bound below the [minimum length](ParSpliter::with_min_len) are not
+    /// split any further.  The default implementation returns `None`, which never prevents
+    /// splitting.
+    fn max_items_per_branch(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether splitting is worthwhile right now.
+    ///
+    /// The bridge checks this before every split attempt, so a Spliterator whose state makes
+    /// splitting expensive for a while can veto it without giving up on splitting later.  The
+    /// default implementation always returns `true`.
+    fn should_split(&self) -> bool {
+        true
+    }
+}
+
+/// Conversion into a [Spliterator].
+///
+/// Every Spliterator converts into itself, and collections like [Vec] convert into the
+/// Spliterator that iterates over them by value, so that they can call
+/// [`par_split()`](ParallelSpliterator::par_split) directly.
+pub trait IntoSpliterator {
+    /// The type of the items.
+    type Item;
+    /// The Spliterator this turns into.
+    type IntoSpliter: Spliterator<Item = Self::Item>;
+
+    /// Convert this into a Spliterator.
+    fn into_spliter(self) -> Self::IntoSpliter;
+}
+
+impl<T: Spliterator> IntoSpliterator for T {
+    type Item = T::Item;
+    type IntoSpliter = T;
+
+    fn into_spliter(self) -> Self::IntoSpliter {
+        self
+    }
 }
 
-/// Converts a [Spliterator] into a [ParallelIterator].
+impl<T> IntoSpliterator for Vec<T> {
+    type Item = T;
+    type IntoSpliter = VecSpliter<T>;
+
+    fn into_spliter(self) -> Self::IntoSpliter {
+        VecSpliter::new(self)
+    }
+}
+
+/// Converts a [Spliterator] (or anything that [converts into one](IntoSpliterator)) into a
+/// [ParallelIterator].
 pub trait ParallelSpliterator: Sized {
+    /// The Spliterator to parallelize.
+    ///
+    /// For a Spliterator, this is just `Self`, so [`par_split()`](Self::par_split) returns a
+    /// `ParSpliter<Self>` as it always has.  For other types, it's their
+    /// [`IntoSpliterator::IntoSpliter`].
+    type Spliter;
+
     /// Parallelize this.
-    fn par_split(self) -> ParSpliter<Self>;
+    fn par_split(self) -> ParSpliter<Self::Spliter>;
 }
 
 impl<T> ParallelSpliterator for T
 where
-    T: Spliterator + Send,
+    T: IntoSpliterator,
+    T::IntoSpliter: Send,
     T::Item: Send,
 {
-    fn par_split(self) -> ParSpliter<Self> {
-        ParSpliter::new(self)
+    type Spliter = T::IntoSpliter;
+
+    fn par_split(self) -> ParSpliter<Self::Spliter> {
+        ParSpliter::new(self.into_spliter())
     }
 }
 
 /// An adapter from a [Spliterator] to a [ParallelIterator].
+///
+/// All of [ParallelIterator]'s methods are driven through the splitting strategy described in the
+/// [crate docs](crate).  Combinators that carry per-branch state, like
+/// [`for_each_with()`](ParallelIterator::for_each_with), get a fresh copy of it for every branch.
+/// Fallible ones like [`try_for_each_with()`](ParallelIterator::try_for_each_with) stop every
+/// branch once any of them fails, though which error is returned is not deterministic.  Likewise,
+/// searches like [`find_map_any()`](ParallelIterator::find_map_any) stop every branch as soon as
+/// any of them finds a match, and return whichever match was found first.
+///
+/// To expand each item into many, [`flat_map_iter()`](ParallelIterator::flat_map_iter) iterates
+/// over each expansion sequentially, inside the branch that produced the item, while the
+/// Spliterator itself keeps splitting as usual.
+///
+/// Within a branch, items are always consumed in [`next()`](Iterator::next) order, so per-branch
+/// folds like [`fold()`](ParallelIterator::fold) see them in that order too.  Only the order
+/// *between* branches depends on how the work was split.
 #[derive(Clone, Copy, Debug)]
 pub struct ParSpliter<T> {
     /// The underlying Spliterator.
     iter: T,
     /// The number of pieces we'd like to split into.
     splits: usize,
+    /// The number of splits between the root and this branch.
+    depth: usize,
+    /// Options that control the bridge.
+    config: Config,
+}
+
+/// Options that control the bridge, shared by every branch.
+#[derive(Clone, Copy, Debug)]
+struct Config {
+    /// Whether to tune the number of items consumed between split attempts.
+    auto_granularity: bool,
+    /// The maximum depth of the split tree.
+    max_depth: usize,
+    /// Whether to skip splitting entirely.
+    sequential: bool,
+    /// The minimum number of items a branch must have to be worth splitting.
+    min_len: usize,
+    /// The ID of the traced run, if any.
+    trace: Option<u64>,
+    /// Whether the Spliterator enforces its own split budget, instead of the bridge.
+    self_budgeted: bool,
+    /// Whether to make the split tree independent of scheduling.
+    deterministic: bool,
+    /// Whether the number of threads is capped, so steals shouldn't reset the split budget.
+    capped: bool,
+    /// Whether to stop splitting once a branch starts consuming items.
+    eager: bool,
+    /// The number of times a branch retries a failed split after consuming more items.
+    split_retries: usize,
+    /// The result size (in bytes) above which to merge in a balanced tree.
+    reduce_threshold: usize,
+    /// The fraction of the split budget that a branch keeps when it splits.
+    split_ratio: f64,
+    /// Whether to hold off on splitting while the last split is still waiting to be stolen.
+    on_demand: bool,
+    /// Whether to run sequentially if the pool only has one thread.
+    single_thread_mode: bool,
+    /// If set, split any branch with more items than this, regardless of the split budget.
+    balance_threshold: Option<usize>,
+    /// The largest split budget a branch can have.
+    max_splits: usize,
+    /// Whether results must be combined in split-tree order, which rules out the work queue.
+    ordered: bool,
+    /// The name to give the tracing spans, if any.
+    #[cfg(feature = "tracing")]
+    tracing_name: Option<&'static str>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            auto_granularity: false,
+            max_depth: usize::MAX,
+            sequential: false,
+            min_len: 2,
+            trace: None,
+            self_budgeted: false,
+            deterministic: false,
+            capped: false,
+            eager: false,
+            split_retries: usize::MAX,
+            reduce_threshold: usize::MAX,
+            split_ratio: 0.5,
+            on_demand: false,
+            single_thread_mode: false,
+            balance_threshold: None,
+            max_splits: usize::MAX,
+            ordered: false,
+            #[cfg(feature = "tracing")]
+            tracing_name: None,
+        }
+    }
 }
 
 impl<T: Spliterator> ParSpliter<T> {
+    /// The largest split budget allowed by
+    /// [`with_overflow_checked_splits()`](Self::with_overflow_checked_splits).
+    pub const MAX_SPLITS: usize = 1024;
+
     fn new(iter: T) -> Self {
         Self {
             iter,
             splits: current_num_threads(),
+            depth: 0,
+            config: Config::default(),
+        }
+    }
+
+    /// Wrap the underlying Spliterator in an adapter, keeping the split state.
+    fn adapt<U, F>(self, f: F) -> ParSpliter<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        ParSpliter {
+            iter: f(self.iter),
+            splits: self.splits,
+            depth: self.depth,
+            config: self.config,
+        }
+    }
+
+    /// Check whether the run will skip the bridge, and consume everything in one pass.
+    fn runs_sequentially(&self) -> bool {
+        let single_thread = self.config.single_thread_mode && current_num_threads() == 1;
+        self.config.sequential || single_thread
+    }
+
+    /// Check whether this branch is allowed to split any more.
+    fn has_budget(&self) -> bool {
+        // Ordered runs can't fall back to the work queue, so they just stop splitting
+        if self.config.ordered && self.depth >= self.config.max_depth {
+            return false;
+        }
+
+        if let Some(threshold) = self.config.balance_threshold {
+            estimated_len(&self.iter) > threshold
+        } else {
+            self.splits > 0 || self.config.self_budgeted
         }
     }
 
     fn split(&mut self) -> Option<Self> {
-        if self.splits == 0 {
+        if !self.has_budget() {
+            return None;
+        }
+
+        if let Some(max) = self.iter.max_items_per_branch() {
+            if max < self.config.min_len {
+                return None;
+            }
+        }
+
+        if !self.iter.should_split() {
+            return None;
+        }
+
+        // Nobody has taken our last split yet, so nobody is waiting for another one
+        if self.config.on_demand && rayon_core::current_thread_has_pending_tasks() == Some(true) {
             return None;
         }
 
         if let Some(split) = self.iter.split() {
-            self.splits /= 2;
+            let splits = self.splits as f64;
+            let keep = splits * self.config.split_ratio;
+            self.splits = keep as usize;
+            self.depth = self.depth.saturating_add(1);
             Some(Self {
                 iter: split,
-                splits: self.splits,
+                splits: (splits - keep) as usize,
+                depth: self.depth,
+                config: self.config,
             })
         } else {
             None
@@ -80,38 +400,248 @@ impl<T: Spliterator> ParSpliter<T> {
     {
         // Thief-splitting: start with enough splits to fill the thread pool,
         // and reset every time a job is stolen by another thread.
-        if stolen {
-            self.splits = current_num_threads();
+        let _span = BranchSpan::enter(&self.config, self.depth);
+        let mut tracer = Tracer::new(self.config.trace, self.depth);
+        if stolen && !self.config.deterministic && !self.config.capped {
+            self.splits = current_num_threads().min(self.config.max_splits);
+            tracer.steal_reset();
+        }
+
+        // Too deep to keep recursing, so switch to an explicit work queue
+        if self.depth >= self.config.max_depth && self.has_budget() {
+            return self.bridge_queue(consumer);
         }
 
         let mut folder = consumer.split_off_left().into_folder();
 
-        if self.splits == 0 {
-            return folder.consume_iter(&mut self.iter).complete();
+        if !self.has_budget() {
+            let iter = self.iter.by_ref().inspect(|_| tracer.consume());
+            folder = folder.consume_iter(iter);
+            tracer.complete();
+            return folder.complete();
         }
 
+        let tune = self.config.auto_granularity && !self.config.deterministic;
+        let mut tuner = tune.then(Tuner::new);
+
+        // Eager splitting gives up after the first failure
+        let retries = if self.config.eager {
+            0
+        } else {
+            self.config.split_retries
+        };
+        let mut failures = 0;
+
         while !folder.full() {
             // Try to split
-            if let Some(mut split) = self.split() {
-                let (r1, r2) = (consumer.to_reducer(), consumer.to_reducer());
-                let left_consumer = consumer.split_off_left();
+            if let Some(split) = self.split() {
+                tracer.split();
+                return self.fork(split, folder, consumer);
+            }
+
+            failures += 1;
+            if failures > retries {
+                // Give up on splitting, and consume the rest in one go
+                let iter = self.iter.by_ref().inspect(|_| tracer.consume());
+                folder = folder.consume_iter(iter);
+                break;
+            }
+
+            // Otherwise, consume an item (or a batch of them) and try again
+            let batch = tuner.as_mut().map_or(1, Tuner::start);
+            for _ in 0..batch {
+                if let Some(next) = self.iter.next() {
+                    tracer.consume();
+                    folder = folder.consume(next);
+                } else {
+                    tracer.complete();
+                    return folder.complete();
+                }
+
+                if folder.full() {
+                    break;
+                }
+            }
+
+            if let Some(tuner) = &mut tuner {
+                tuner.finish();
+            }
+        }
+
+        tracer.complete();
+        folder.complete()
+    }
+
+    /// Bridge both halves of a split in parallel, and combine them with what we've folded so far.
+    fn fork<C>(&mut self, mut split: Self, folder: C::Folder, consumer: C) -> C::Result
+    where
+        T: Send,
+        C: UnindexedConsumer<T::Item>,
+    {
+        let (r1, r2) = (consumer.to_reducer(), consumer.to_reducer());
+        let left_consumer = consumer.split_off_left();
+
+        // The split-off half may be stolen, so keep it under the same parent span
+        let parent = ParentSpan::current();
+        let (left, right) = join_context(
+            |ctx| self.bridge(ctx.migrated(), left_consumer),
+            |ctx| parent.in_scope(|| split.bridge(ctx.migrated(), consumer)),
+        );
+        r1.reduce(folder.complete(), r2.reduce(left, right))
+    }
+
+    /// Run a branch that's too deep to recurse any further, using a heap-allocated work queue.
+    ///
+    /// Instead of forking, split-off halves are pushed onto a shared queue, and helper tasks (up
+    /// to one per thread in the pool) are spawned to pop pieces off it and fold them.  Nothing
+    /// here recurses, so the stack depth stays bounded no matter how many splits happen.  Pieces
+    /// are never considered stolen, so they keep whatever split budget they had.
+    fn bridge_queue<C>(&mut self, consumer: C) -> C::Result
+    where
+        T: Send,
+        C: UnindexedConsumer<T::Item>,
+    {
+        let own = consumer.split_off_left();
+        let queue = WorkQueue::new(consumer);
+
+        let result = rayon::scope(|scope| {
+            let folder = self.fold_queued(&queue, scope, own.into_folder());
+            Self::drain_queue(&queue, scope, folder).complete()
+        });
+
+        let consumer = queue.consumer.into_inner().unwrap();
+        queue
+            .results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .fold(result, |left, right| {
+                consumer.to_reducer().reduce(left, right)
+            })
+    }
+
+    /// Spawn a task to help drain the queue, unless enough are already running.
+    fn spawn_helper<'s, C>(queue: &'s WorkQueue<Self, C, C::Result>, scope: &rayon::Scope<'s>)
+    where
+        T: Send,
+        C: UnindexedConsumer<T::Item>,
+    {
+        let limit = current_num_threads();
+        let reserved = queue
+            .helpers
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                (n < limit).then_some(n + 1)
+            });
+        if reserved.is_err() {
+            return;
+        }
+
+        scope.spawn(move |scope| {
+            let folder = queue
+                .consumer
+                .lock()
+                .unwrap()
+                .split_off_left()
+                .into_folder();
+            let result = Self::drain_queue(queue, scope, folder).complete();
+            queue.results.lock().unwrap().push(result);
+            queue.helpers.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Pop pieces off the queue and fold them, until it's empty.
+    ///
+    /// Every piece that splits is either running or queued, so whoever pushes the last piece will
+    /// always find it here once they're done with their own.
+    fn drain_queue<'s, C>(
+        queue: &'s WorkQueue<Self, C, C::Result>,
+        scope: &rayon::Scope<'s>,
+        mut folder: C::Folder,
+    ) -> C::Folder
+    where
+        T: Send,
+        C: UnindexedConsumer<T::Item>,
+    {
+        while !folder.full() {
+            let piece = queue.pieces.lock().unwrap().pop();
+            match piece {
+                Some(mut piece) => folder = piece.fold_queued(queue, scope, folder),
+                None => break,
+            }
+        }
+
+        folder
+    }
+
+    /// Fold this piece, pushing anything it splits off onto the queue.
+    fn fold_queued<'s, C>(
+        &mut self,
+        queue: &'s WorkQueue<Self, C, C::Result>,
+        scope: &rayon::Scope<'s>,
+        mut folder: C::Folder,
+    ) -> C::Folder
+    where
+        T: Send,
+        C: UnindexedConsumer<T::Item>,
+    {
+        let _span = BranchSpan::enter(&self.config, self.depth);
+        let mut tracer = Tracer::new(self.config.trace, self.depth);
+
+        let retries = if self.config.eager {
+            0
+        } else {
+            self.config.split_retries
+        };
+        let mut failures = 0;
 
-                let (left, right) = join_context(
-                    |ctx| self.bridge(ctx.migrated(), left_consumer),
-                    |ctx| split.bridge(ctx.migrated(), consumer),
-                );
-                return r1.reduce(folder.complete(), r2.reduce(left, right));
+        while !folder.full() {
+            if let Some(split) = self.split() {
+                tracer.split();
+                queue.pieces.lock().unwrap().push(split);
+                Self::spawn_helper(queue, scope);
+                continue;
+            }
+
+            failures += 1;
+            if failures > retries || !self.has_budget() {
+                let iter = self.iter.by_ref().inspect(|_| tracer.consume());
+                folder = folder.consume_iter(iter);
+                break;
             }
 
-            // Otherwise, consume an item and try again
             if let Some(next) = self.iter.next() {
+                tracer.consume();
                 folder = folder.consume(next);
             } else {
                 break;
             }
         }
 
-        folder.complete()
+        tracer.complete();
+        folder
+    }
+}
+
+/// The shared state of [`ParSpliter::bridge_queue()`].
+struct WorkQueue<P, C, R> {
+    /// The pieces waiting to be folded.
+    pieces: Mutex<Vec<P>>,
+    /// The consumer to split off helper folders from.
+    consumer: Mutex<C>,
+    /// The results of the finished helpers.
+    results: Mutex<Vec<R>>,
+    /// The number of helpers running.
+    helpers: AtomicUsize,
+}
+
+impl<P, C, R> WorkQueue<P, C, R> {
+    fn new(consumer: C) -> Self {
+        Self {
+            pieces: Mutex::new(Vec::new()),
+            consumer: Mutex::new(consumer),
+            results: Mutex::new(Vec::new()),
+            helpers: AtomicUsize::new(0),
+        }
     }
 }
 
@@ -126,55 +656,3517 @@ where
     where
         C: UnindexedConsumer<Self::Item>,
     {
-        self.bridge(false, consumer)
+        if self.runs_sequentially() {
+            consumer.into_folder().consume_iter(self.iter).complete()
+        } else {
+            self.bridge(false, consumer)
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl<T: Spliterator> ParSpliter<T> {
+    /// Check whether running this in the current thread pool would actually split the work.
+    ///
+    /// Returns `false` if the pool has only one thread, if there is no split budget left, or if
+    /// the run will skip splitting anyway, like a [sequential](Self::sequential) run.  Uses the
+    /// same checks as the run itself, so it agrees with
+    /// [`single_thread_mode()`](Self::single_thread_mode) too.
+    pub fn will_parallelize(&self) -> bool {
+        !self.runs_sequentially() && current_num_threads() > 1 && self.splits > 0
+    }
 
-    #[test]
-    fn test_par_split() {
-        struct AllNumbers {
-            stack: Vec<u32>,
-        }
+    /// Get the underlying Spliterator back, to iterate over sequentially.
+    ///
+    /// Every adapter in this crate is a Spliterator itself, so the result still applies them,
+    /// just on a single thread.  Together with [`sequential()`](Self::sequential), this lets
+    /// generic code choose at runtime whether to go parallel.
+    pub fn seq_iter(self) -> T {
+        self.iter
+    }
 
-        impl AllNumbers {
-            fn new() -> Self {
-                Self { stack: vec![1] }
-            }
-        }
+    /// Automatically tune how often each branch tries to split.
+    ///
+    /// Normally, a branch tries to split again after every item it consumes.  In this mode, each
+    /// branch times its work, and consumes larger batches of items between split attempts when
+    /// items are cheap, or smaller ones when they are expensive.  This costs a clock read per
+    /// batch, which is only worthwhile when [`Spliterator::split()`] is not itself cheap.
+    pub fn with_auto_granularity(mut self) -> Self {
+        self.config.auto_granularity = true;
+        self
+    }
 
-        impl Iterator for AllNumbers {
-            type Item = u32;
+    /// Don't split branches that will yield fewer than `len` items.
+    ///
+    /// This relies on [`Spliterator::max_items_per_branch()`], so it has no effect on
+    /// Spliterators that don't implement it.  The default is 2, since a branch with at most one
+    /// item is never worth splitting.
+    pub fn with_min_len(mut self, len: usize) -> Self {
+        self.config.min_len = len;
+        self
+    }
 
-            fn next(&mut self) -> Option<Self::Item> {
-                if let Some(n) = self.stack.pop() {
-                    if n < 1 << 15 {
-                        self.stack.push(2 * n);
-                        self.stack.push(2 * n + 1);
-                    }
-                    Some(n)
-                } else {
-                    None
-                }
-            }
-        }
+    /// Limit the depth of the split tree, to bound how deeply the bridge recurses.
+    ///
+    /// Each split nests another level of [`join_context()`], and since work-stealing threads can
+    /// run stolen branches on top of their own, a long chain of lopsided splits could overflow the
+    /// stack.  Once a branch is `depth` splits away from the root, it stops recursing, but keeps
+    /// splitting: the halves it splits off go onto a heap-allocated work queue instead, which the
+    /// threads of the pool drain in a loop.  Branches run from the queue are not combined in
+    /// split-tree order, and aren't treated as stolen, so they don't get a fresh split budget.
+    ///
+    /// Methods that promise split-tree order, like
+    /// [`fold_ordered_per_branch()`](Self::fold_ordered_per_branch),
+    /// [`run_length_encode()`](Self::run_length_encode),
+    /// [`concat_strings()`](Self::concat_strings), [`concat_vecs()`](Self::concat_vecs) and
+    /// [`reduce_deterministic()`](Self::reduce_deterministic), don't use the queue: their
+    /// branches just stop splitting at `depth` instead.
+    pub fn with_max_recursion(mut self, depth: usize) -> Self {
+        self.config.max_depth = depth;
+        self
+    }
 
-        impl Spliterator for AllNumbers {
-            fn split(&mut self) -> Option<Self> {
-                let len = self.stack.len();
-                if len >= 2 {
-                    let split = self.stack.split_off(len / 2);
-                    Some(Self { stack: split })
-                } else {
-                    None
-                }
-            }
-        }
+    /// Run sequentially, without splitting at all.
+    ///
+    /// The whole Spliterator is folded in a single pass on the current thread, without going
+    /// through Rayon's scheduler.  This lets code that is generic over parallelism use the same
+    /// [ParallelIterator] pipeline for a single-threaded build.
+    pub fn sequential(mut self) -> Self {
+        self.config.sequential = true;
+        self
+    }
+
+    /// Combine results in split-tree order, by not splitting past the maximum recursion depth.
+    fn ordered(mut self) -> Self {
+        self.config.ordered = true;
+        self
+    }
+
+    /// Run [sequentially](Self::sequential) if the thread pool only has one thread.
+    ///
+    /// In a single-threaded pool, the split budget starts at one, so the bridge splits once and
+    /// never again, since nothing can ever be stolen.  With this option, the bridge is skipped
+    /// entirely in that case: the Spliterator is never split, and its items are folded in one
+    /// pass, without checking whether the consumer is full or setting up any joins.  The pool is
+    /// checked when the iterator is run, not when this is called, and larger pools are
+    /// unaffected.
+    pub fn single_thread_mode(mut self) -> Self {
+        self.config.single_thread_mode = true;
+        self
+    }
+
+    /// Cap the split budget at a sane maximum, for enormous thread pools.
+    ///
+    /// The split budget normally starts at (and is reset to) the number of threads in the pool.
+    /// With hundreds of cores, that can mean a lot of splitting before any branch settles down
+    /// to work.  This option clamps the budget to at most
+    /// [`MAX_SPLITS`](Self::MAX_SPLITS), both now and whenever a branch is stolen.
+    pub fn with_overflow_checked_splits(mut self) -> Self {
+        self.config.max_splits = Self::MAX_SPLITS;
+        self.splits = self.splits.min(Self::MAX_SPLITS);
+        self
+    }
+
+    /// Use at most `m` threads for this run, even in a larger pool.
+    ///
+    /// Rayon has no way to limit the threads used by a single call, so this limits the splits
+    /// instead: branches never get a fresh split budget when they're stolen, and the initial
+    /// budget is cut down so that there are at most `m` branches.  Since each branch runs
+    /// entirely on one thread, that's also a bound on the number of threads.  The budget only
+    /// comes in powers of two, so up to half of the `m` threads may go unused.
+    pub fn with_max_threads(mut self, m: usize) -> Self {
+        let branches = if m == 0 { 1 } else { 1 << m.ilog2() };
+        self.splits = self.splits.min(branches - 1);
+        self.config.capped = true;
+        self
+    }
+
+    /// Keep splitting heavy branches until every branch is a similar size.
+    ///
+    /// This replaces the split budget with a size limit: any branch with more than `fraction`
+    /// times its fair share of the items (the root's size divided by the number of threads in
+    /// the current pool) splits, and any branch with fewer doesn't, whether or not it was
+    /// stolen.  That keeps splitting heavy branches on skewed data, where the Spliterator splits
+    /// off much less than half of its items, long after the split budget would have run out.
+    ///
+    /// The sizes come from [`size_hint()`](Iterator::size_hint), using the upper bound if there
+    /// is one and the lower bound otherwise, so this mode needs exact (or at least tight) size
+    /// hints.  If the hints are missing or inaccurate, the shape of the split tree will be too: a
+    /// Spliterator without any hints will never split at all.  The adapters in this crate forward
+    /// the hints of the Spliterator they wrap, so they can be stacked in either order.
+    pub fn split_until_balanced(mut self, fraction: f64) -> Self {
+        let share = estimated_len(&self.iter) as f64 / current_num_threads() as f64;
+        self.config.balance_threshold = Some((share * fraction) as usize);
+        self
+    }
+
+    /// Split as far as possible up front, and never once consuming has started.
+    ///
+    /// This is Rayon's usual strategy: each branch keeps splitting while its budget lasts, but
+    /// as soon as it starts consuming items, it never splits again.  That saves the split
+    /// attempt before every item when the frontier is already large and doesn't grow, but it
+    /// won't parallelize at all if the root can't split before consuming something.
+    pub fn split_eagerly(mut self) -> Self {
+        self.config.eager = true;
+        self
+    }
+
+    /// Only split when another thread is ready to take the work.
+    ///
+    /// Normally, a branch with split budget left splits as soon as it can, pushing work onto its
+    /// thread's queue whether or not any other thread is idle.  In this mode, a branch also
+    /// refuses to split while its thread's queue isn't empty.  Split-off halves are pushed onto
+    /// that queue, so the queue only empties out when a thief steals them (or the thread gets
+    /// to them itself), and the next split waits until then.  Each thread therefore has at most
+    /// one unstolen split outstanding at a time, which saves most of the wasted splits when the
+    /// other threads are busy anyway.  Refused splits count as failed attempts, so the branch
+    /// consumes some items and tries again later, as usual.
+    ///
+    /// The queue belongs to the thread, not the iterator, so any other pending Rayon work on the
+    /// same thread (like the other half of an enclosing [`join()`](rayon::join)) also holds off
+    /// splitting.
+    pub fn split_on_demand(mut self) -> Self {
+        self.config.on_demand = true;
+        self
+    }
+
+    /// Limit how many times a branch retries a failed split.
+    ///
+    /// Normally, when a split fails, the bridge consumes an item (or a batch of them) and tries
+    /// again, for as long as the branch has items and split budget left.  With this option, each
+    /// branch gives up on splitting after `attempts` retries, and folds the rest of its items
+    /// without checking again.  Both halves of a successful split start counting from zero.  An
+    /// `attempts` of zero means only trying to split before consuming anything, like
+    /// [`split_eagerly()`](Self::split_eagerly).
+    pub fn with_split_retry(mut self, attempts: usize) -> Self {
+        self.config.split_retries = attempts;
+        self
+    }
+
+    /// Make [`fold_reduce()`](Self::fold_reduce) merge in a balanced tree when the result type
+    /// is larger than `bytes`.
+    ///
+    /// This is only a heuristic: it looks at [`size_of()`](std::mem::size_of) the result type,
+    /// which says nothing about any heap allocations it owns.
+    pub fn with_reduce_threshold(mut self, bytes: usize) -> Self {
+        self.config.reduce_threshold = bytes;
+        self
+    }
+
+    /// Control how the split budget is divided between the two halves of a split.
+    ///
+    /// Normally, both halves get half of the remaining budget.  With this option, the branch
+    /// that keeps going gets a fraction `r` of it (rounded down), and the branch that was split
+    /// off gets the rest.  A ratio above one half makes the split tree lean towards the original
+    /// branch, which suits workloads where the work that stays behind tends to be bigger.  `r` is
+    /// clamped to `[0, 1]`.
+    pub fn with_split_ratio(mut self, r: f64) -> Self {
+        self.config.split_ratio = r.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Split the same way every time, regardless of scheduling.
+    ///
+    /// This turns off thief-splitting: branches no longer get a fresh split budget when they're
+    /// stolen, so the split tree depends only on the Spliterator and the number of threads in the
+    /// pool.  It also turns off [automatic granularity](Self::with_auto_granularity), which depends
+    /// on timing.  Adapters that share state between branches, like
+    /// [`limit_split()`](Self::limit_split), can still make the run nondeterministic.
+    pub fn deterministic(mut self) -> Self {
+        self.config.deterministic = true;
+        self
+    }
+
+    /// Yield at most `n` items in total, across all branches.
+    ///
+    /// Once `n` items have been produced, every branch stops pulling from the underlying
+    /// Spliterator.  Unlike a sequential [`take()`](Iterator::take), *which* `n` items are produced
+    /// depends on how the work was scheduled, and is not deterministic.
+    pub fn limit_split(self, n: usize) -> ParSpliter<Limit<T>> {
+        self.adapt(|iter| Limit::new(iter, n))
+    }
+
+    /// Keep only every `k`th item *within each branch*.
+    ///
+    /// Each branch keeps its first item, then every `k`th one after that, with the count starting
+    /// over whenever a new branch is split off.  Since there is no global order, the total number
+    /// of items kept depends on the shape of the split tree, and will generally be somewhat more
+    /// than `1/k` of the total.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn sample_every_branch(self, k: usize) -> ParSpliter<SampleEvery<T>> {
+        self.adapt(|iter| SampleEvery::new(iter, k))
+    }
+
+    /// Tag each item with the path through the split tree to the branch that produced it.
+    ///
+    /// Every split appends [`Side::Left`] to the path of the current branch, and [`Side::Right`]
+    /// to the path of the branch that was split off.  Items produced before a split therefore
+    /// have a path that is a prefix of the paths of items produced after it.
+    pub fn with_branch_path(self) -> ParSpliter<BranchPath<T>> {
+        self.adapt(BranchPath::new)
+    }
+
+    /// Drop consecutive duplicate items *within each branch*.
+    ///
+    /// Within a branch, this behaves like [`Vec::dedup()`].  Runs of equal items that straddle a
+    /// split are not collapsed, since they end up in different branches.
+    pub fn dedup_branch(self) -> ParSpliter<DedupBranch<T>>
+    where
+        T::Item: PartialEq,
+    {
+        self.adapt(DedupBranch::new)
+    }
+
+    /// Skip items that were probably already seen *within each branch*.
+    ///
+    /// Each branch keeps its own bloom filter of `bits` bits (rounded up to a multiple of 64), and
+    /// a fresh one is allocated for every split.  This cuts down on redundant work in searches
+    /// that revisit the same nodes, without any coordination between threads.  It's only
+    /// approximate, in both directions:
+    ///
+    /// - Revisits across branches are not caught, so items can still appear more than once.
+    /// - A false positive in the filter will skip an item that hasn't been seen before.  The
+    ///   chance of this grows as the filter fills up, so make it large enough for the number of
+    ///   distinct items per branch.
+    ///
+    /// An item that *was* already seen in the same branch is always skipped.
+    pub fn with_branch_bloom(self, bits: usize) -> ParSpliter<BranchBloom<T>>
+    where
+        T::Item: Hash,
+    {
+        self.adapt(|iter| BranchBloom::new(iter, bits))
+    }
+
+    /// Pair each item with its index *within its branch*.
+    ///
+    /// Every branch numbers its items starting from zero, so the indices are **not** globally
+    /// unique.  They only identify an item's position among the others from the same branch.
+    pub fn enumerate_branch(self) -> ParSpliter<EnumerateBranch<T>> {
+        self.adapt(EnumerateBranch::new)
+    }
+
+    /// Unwrap `Some` items, stopping each branch at its first `None`.
+    ///
+    /// Unlike [`while_some()`](ParallelIterator::while_some), this doesn't stop the whole run.
+    /// Each branch truncates at the first `None` that *it* sees, but branches that were already
+    /// split off keep going, so items from "after" the `None` in sequential order can still be
+    /// produced.
+    pub fn while_some_branch<R>(self) -> ParSpliter<WhileSomeBranch<T>>
+    where
+        T: Spliterator<Item = Option<R>>,
+    {
+        self.adapt(WhileSomeBranch::new)
+    }
+
+    /// Stop early if a shared flag is set, e.g. from another thread.
+    ///
+    /// Every branch checks the flag before splitting and before producing each item.  Once it is
+    /// set, all branches stop and the run completes with whatever partial result the consumer has
+    /// accumulated so far, e.g. a partial [`count()`](ParallelIterator::count) or
+    /// [`collect()`](ParallelIterator::collect).
+    pub fn with_cancel(self, flag: Arc<AtomicBool>) -> ParSpliter<Cancel<T>> {
+        self.adapt(|iter| Cancel::new(iter, flag))
+    }
+
+    /// Consume each branch from the back, with [`next_back()`](DoubleEndedIterator::next_back).
+    ///
+    /// Splitting still works the same way, but within each branch, items are produced in reverse.
+    /// For stack-based searches, this prefers the newest part of the frontier.  Requires the
+    /// Spliterator to be a [DoubleEndedIterator].
+    pub fn rev_branch(self) -> ParSpliter<RevBranch<T>>
+    where
+        T: DoubleEndedIterator,
+    {
+        self.adapt(RevBranch::new)
+    }
+
+    /// Call [`std::thread::yield_now()`] every `n` items in each branch.
+    ///
+    /// This gives other threads a chance to run during long-running branches, in workloads that
+    /// share the machine with other work.  An `n` of zero means never yield.
+    pub fn with_yield_every(self, n: usize) -> ParSpliter<YieldEvery<T>> {
+        self.adapt(|iter| YieldEvery::new(iter, n))
+    }
+
+    /// Count exactly how many items are consumed, into a shared counter.
+    ///
+    /// The bridge only pulls an item from the Spliterator when it is about to consume it, so
+    /// after the run, the counter holds the number of items that were actually processed.  With
+    /// short-circuiting methods like [`find_any()`](ParallelIterator::find_any), that can be fewer
+    /// than the total.  Unlike [`with_progress()`](Self::with_progress), the counter is updated
+    /// for every item.
+    pub fn with_item_counter(self, counter: Arc<AtomicUsize>) -> ParSpliter<ItemCounter<T>> {
+        self.adapt(|iter| ItemCounter::new(iter, counter))
+    }
+
+    /// Count the leaf branches of the split tree into a shared counter.
+    ///
+    /// A leaf is the last stretch of a branch, after its final split, so every successful split
+    /// adds one more.  Each leaf is counted when it finishes, so after the run, the counter holds
+    /// the effective number of independent work units.
+    pub fn with_branch_counter(self, counter: Arc<AtomicUsize>) -> ParSpliter<BranchCounter<T>> {
+        self.adapt(|iter| BranchCounter::new(iter, counter))
+    }
+
+    /// Count the items produced into a shared counter, which can be polled to report progress.
+    ///
+    /// To limit contention, each branch only updates the counter every few hundred items, so it
+    /// may lag behind slightly during the run.  When the run completes, the counter is exact.
+    pub fn with_progress(self, counter: Arc<AtomicU64>) -> ParSpliter<Progress<T>> {
+        self.adapt(|iter| Progress::new(iter, counter))
+    }
+
+    /// Report live metrics about the run to `f`, every `interval`.
+    ///
+    /// When the run starts, this spawns a monitor thread, which wakes up every `interval` and
+    /// passes a [LiveMetrics] snapshot to `f`.  The item counts are batched like
+    /// [`with_progress()`](Self::with_progress), so they may lag behind slightly.  Once the last
+    /// branch finishes, the monitor makes one final report with the exact counts and stops, and
+    /// the run waits for it before returning.  If the iterator is dropped without running, no
+    /// thread is spawned and `f` is never called.
+    pub fn with_metrics_sink<F>(self, interval: Duration, f: F) -> ParSpliter<MetricsSink<T>>
+    where
+        F: Fn(LiveMetrics) + Send + Sync + 'static,
+    {
+        self.adapt(|iter| MetricsSink::new(iter, interval, f))
+    }
+
+    /// Expand the root into at least `target` independent sub-iterators before going parallel.
+    ///
+    /// Searches that start from a single seed can't split until they've consumed a few items, so
+    /// the first few levels of the split tree are serial.  This method expands the frontier up
+    /// front instead, by calling [`split()`](Spliterator::split) where possible and
+    /// [`next()`](Iterator::next) otherwise.  Any items consumed this way are buffered and
+    /// yielded by the root branch, so the result contains exactly the same items.
+    pub fn prime(self, target: usize) -> ParSpliter<Primed<T>> {
+        self.adapt(|iter| Primed::new(iter, target))
+    }
+
+    /// Keep each branch on the NUMA node of the branch it split from.
+    ///
+    /// The root branch stays on whatever node it starts on, and every branch split off from it
+    /// pins the worker that runs it to the same node, so the memory it touches stays local.  A
+    /// branch only moves to another node when it's stolen by a worker that's already pinned
+    /// there, and its own children follow it.  Each worker gets its old affinity back when the
+    /// branch that pinned it finishes, so nothing leaks into later uses of the pool.
+    ///
+    /// This is best-effort: it's only implemented on Linux (elsewhere it does nothing), threads
+    /// outside of a Rayon pool are never pinned, and errors are ignored.
+    #[cfg(feature = "numa")]
+    pub fn with_numa_affinity(self) -> ParSpliter<NumaAffinity<T>> {
+        self.adapt(NumaAffinity::new)
+    }
+
+    /// Draw the split budget from a shared token bucket that refills over time.
+    ///
+    /// With plain thief-splitting, a branch that has used up its budget only splits again if it
+    /// gets stolen.  For long streaming searches, that can leave the load unbalanced for a long
+    /// time.  With this option, every split instead takes a token from a bucket shared by all
+    /// branches, which starts with `capacity` tokens and gains `rate` more per second (up to
+    /// `capacity`), so the run keeps re-splitting periodically.  The downside is that every split
+    /// attempt touches the shared bucket, which can be contended with many threads.
+    pub fn with_split_token_bucket(
+        mut self,
+        rate: f64,
+        capacity: usize,
+    ) -> ParSpliter<SplitTokenBucket<T>> {
+        self.config.self_budgeted = true;
+        self.adapt(|iter| SplitTokenBucket::new(iter, rate, capacity))
+    }
+
+    /// Cap the rate of items to `per_sec` per second, across all branches.
+    ///
+    /// The branches share a schedule of evenly spaced slots, like a token bucket that holds a
+    /// single token.  Each item waits for the next free slot before it's handed to the consumer,
+    /// by sleeping the thread if necessary.  This deliberately reduces the parallelism to honor
+    /// the rate: once the limit is reached, most threads spend their time asleep.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `per_sec` is zero.
+    pub fn with_rate_limit(self, per_sec: u32) -> ParSpliter<RateLimit<T>> {
+        self.adapt(|iter| RateLimit::new(iter, per_sec))
+    }
+
+    /// Call `f` the first time each thread runs a branch of this iterator.
+    ///
+    /// Unlike [`map_init()`](ParallelIterator::map_init), which initializes once per branch,
+    /// this runs `f` at most once per thread for each run, which is useful for setting up
+    /// thread-local caches.  Each time the iterator (or a clone of it) is driven counts as a new
+    /// run.  Only the most recent run is remembered on
+    /// each thread, so a thread that interleaves branches from several runs (e.g. nested
+    /// parallel iterators) may call `f` again.
+    pub fn with_worker_init<F>(self, f: F) -> ParSpliter<WorkerInit<T, F>>
+    where
+        F: Fn() + Send + Sync,
+    {
+        self.adapt(|iter| WorkerInit::new(iter, f))
+    }
+
+    /// Panic if any item is produced more than once, in debug builds.
+    ///
+    /// The most common bug in a [Spliterator] implementation is a `split()` that hands the same
+    /// work to both halves.  This records every item in a set shared by all branches, and panics
+    /// as soon as one shows up twice.  That's slow, so the check is only done with debug
+    /// assertions enabled; in release builds, this adapter does nothing.
+    pub fn with_duplicate_check(self) -> ParSpliter<DuplicateCheck<T>>
+    where
+        T::Item: Clone + Debug + Eq + Hash,
+    {
+        self.adapt(DuplicateCheck::new)
+    }
+
+    /// Record where every branch splits while running `f` on this iterator.
+    ///
+    /// The [SplitSchedule] that's returned alongside the result of `f` can be passed to
+    /// [`replay()`](Self::replay) later, to reproduce the exact same split tree.  That's useful
+    /// for debugging performance problems that depend on how the work happened to be split.
+    pub fn capture_schedule<F, R>(self, f: F) -> (R, SplitSchedule)
+    where
+        F: FnOnce(ParSpliter<Scheduled<T>>) -> R,
+    {
+        let mut schedule = None;
+        let iter = self.adapt(|iter| {
+            let (iter, shared) = Scheduled::capture(iter);
+            schedule = Some(shared);
+            iter
+        });
+        let result = f(iter);
+
+        let schedule = schedule.unwrap();
+        let schedule = std::mem::take(&mut *schedule.lock().unwrap());
+        (result, schedule)
+    }
+
+    /// Split exactly where a [captured](Self::capture_schedule) schedule says to.
+    ///
+    /// Every branch tries to split before each of its items, but only succeeds at the point
+    /// where the same branch split in the captured run.  The split budget is ignored, and so are
+    /// the other options that decide when to try splitting, like
+    /// [auto-granularity](Self::with_auto_granularity) and [eager](Self::split_eagerly) or
+    /// [on-demand](Self::split_on_demand) splitting, so the split tree doesn't depend on
+    /// scheduling at all.  As long as the Spliterator itself is deterministic, this reproduces
+    /// the captured split tree exactly.  Both capturing and replaying stop splitting branches at
+    /// [`BranchId::MAX_DEPTH`], so that every branch in the schedule has a unique ID.
+    pub fn replay(mut self, schedule: &SplitSchedule) -> ParSpliter<Scheduled<T>> {
+        self.config.self_budgeted = true;
+        self.config.auto_granularity = false;
+        self.config.eager = false;
+        self.config.split_retries = usize::MAX;
+        self.config.on_demand = false;
+        self.config.balance_threshold = None;
+        self.adapt(|iter| Scheduled::replay(iter, schedule))
+    }
+
+    /// Record a log of the bridge's decisions while running `f` on this iterator.
+    ///
+    /// Every branch records when it splits, when its split budget is reset by a steal, how many
+    /// items it consumed in between, and when it completes, along with its depth in the split
+    /// tree.  The events from all threads are returned sorted by time, alongside the result of
+    /// `f`.  This is meant for observing thief-splitting in action, not for production use.
+    #[cfg(feature = "trace")]
+    pub fn debug_trace<F, R>(mut self, f: F) -> (R, Vec<TraceEvent>)
+    where
+        F: FnOnce(Self) -> R,
+    {
+        let id = trace::begin();
+        self.config.trace = Some(id);
+        let result = f(self);
+        (result, trace::end(id))
+    }
+
+    /// Wrap the work of each branch in a [`tracing`] span named `name`.
+    ///
+    /// Every call to the bridge, which folds a stretch of items and joins the halves of any split
+    /// it makes, runs inside an `INFO`-level span called `branch`, with `name` and the `depth` of
+    /// the branch in the split tree as fields.  Profilers and flamegraphs can then attribute
+    /// time to this iterator.  Halves that are stolen by other threads stay under the span of
+    /// the branch they were split from.
+    #[cfg(feature = "tracing")]
+    pub fn with_tracing_name(mut self, name: &'static str) -> Self {
+        self.config.tracing_name = Some(name);
+        self
+    }
+}
+
+impl<T> ParSpliter<T>
+where
+    T: Spliterator + Send,
+    T::Item: Send,
+{
+    /// Run the same parallel iterator through two different consumers.
+    ///
+    /// The root Spliterator is cloned before any splitting happens, so both runs see exactly the
+    /// same items.  This is mainly useful for debugging, e.g. to compare two ways of computing the
+    /// same result.  The runs happen one after the other.
+    pub fn run_twice<F1, F2, R1, R2>(self, f1: F1, f2: F2) -> (R1, R2)
+    where
+        T: Clone,
+        F1: FnOnce(Self) -> R1,
+        F2: FnOnce(Self) -> R2,
+    {
+        let copy = self.clone();
+        (f1(self), f2(copy))
+    }
+
+    /// Count the items that match a predicate.
+    ///
+    /// Equivalent to `.filter(f).count()`, but fused into a single fold per branch.
+    pub fn count_matching<F>(self, f: F) -> usize
+    where
+        F: Fn(&T::Item) -> bool + Sync,
+    {
+        self.fold(|| 0, |n, item| n + f(&item) as usize).sum()
+    }
+
+    /// Find any item that matches a predicate, or return `default` if there is none.
+    ///
+    /// Like [`find_any()`](ParallelIterator::find_any), every branch stops as soon as a match is
+    /// found, and which match is returned is not deterministic.
+    pub fn find_any_or<P>(self, default: T::Item, p: P) -> T::Item
+    where
+        P: Fn(&T::Item) -> bool + Sync,
+    {
+        self.find_any(&p).unwrap_or(default)
+    }
+
+    /// Sum the items, accumulating directly into a local value in each branch.
+    ///
+    /// Unlike [`sum()`](ParallelIterator::sum), which goes through the generic [`Sum`] consumer,
+    /// this adds items one at a time with [`Add`], starting from the empty sum.
+    pub fn sum_spliter(self) -> T::Item
+    where
+        T::Item: Add<Output = T::Item> + Sum,
+    {
+        let zero = || iter::empty().sum();
+        self.fold(zero, Add::add).reduce(zero, Add::add)
+    }
+
+    /// Multiply the items, accumulating directly into a local value in each branch.
+    ///
+    /// Unlike [`product()`](ParallelIterator::product), which goes through the generic
+    /// [`Product`] consumer, this multiplies items one at a time with [`Mul`], starting from the
+    /// empty product.
+    pub fn product_spliter(self) -> T::Item
+    where
+        T::Item: Mul<Output = T::Item> + Product,
+    {
+        let one = || iter::empty().product();
+        self.fold(one, Mul::mul).reduce(one, Mul::mul)
+    }
+
+    /// Flatten and collect items that are themselves vectors.
+    ///
+    /// Each branch appends the items it sees into a single vector, and the branches are combined
+    /// the same way.  The result is in no particular order.
+    pub fn collect_concat<U>(self) -> Vec<U>
+    where
+        T: Spliterator<Item = Vec<U>>,
+    {
+        let append = |mut left: Vec<U>, mut right: Vec<U>| {
+            left.append(&mut right);
+            left
+        };
+        self.fold(Vec::new, append).reduce(Vec::new, append)
+    }
+
+    /// Get the last item consumed by some branch.
+    ///
+    /// Each branch keeps the last item it consumed, and the right-hand side wins when branches are
+    /// combined.  **Which** item is returned depends on how the work was split and scheduled, so
+    /// it is not deterministic at all; this is only useful when any terminal item will do.
+    pub fn last_any(self) -> Option<T::Item> {
+        self.fold(|| None, |_, item| Some(item))
+            .reduce(|| None, |left, right| right.or(left))
+    }
+
+    /// Find the minimum and maximum items in a single pass.
+    ///
+    /// Returns `None` if there are no items.
+    pub fn min_max(self) -> Option<(T::Item, T::Item)>
+    where
+        T::Item: Ord + Clone,
+    {
+        self.fold(
+            || None,
+            |acc, item| {
+                Some(match acc {
+                    None => (item.clone(), item),
+                    Some((min, max)) if item < min => (item, max),
+                    Some((min, max)) if item > max => (min, item),
+                    Some(acc) => acc,
+                })
+            },
+        )
+        .reduce(
+            || None,
+            |left, right| match (left, right) {
+                (Some((lmin, lmax)), Some((rmin, rmax))) => Some((lmin.min(rmin), lmax.max(rmax))),
+                (left, right) => left.or(right),
+            },
+        )
+    }
+
+    /// Move the items that match a predicate into `out`, and count the ones that don't.
+    ///
+    /// The matching items are appended to `out` in no particular order.  Returns the number of
+    /// items that were discarded.
+    pub fn retain_into<F>(self, out: &mut Vec<T::Item>, f: F) -> usize
+    where
+        F: Fn(&T::Item) -> bool + Sync,
+    {
+        let (mut kept, discarded) = self
+            .fold(
+                || (Vec::new(), 0),
+                |(mut kept, discarded), item| {
+                    if f(&item) {
+                        kept.push(item);
+                        (kept, discarded)
+                    } else {
+                        (kept, discarded + 1)
+                    }
+                },
+            )
+            .reduce(
+                || (Vec::new(), 0),
+                |(mut left, a), (mut right, b)| {
+                    left.append(&mut right);
+                    (left, a + b)
+                },
+            );
+
+        out.append(&mut kept);
+        discarded
+    }
+
+    /// Reduce the items, folding them in batches to amortize the cost of combining results.
+    ///
+    /// Each branch folds `batch` items at a time into a fresh accumulator from `identity`, and
+    /// merges each full batch into its running result with `reduce`.  The branch results are then
+    /// combined with `reduce` as well.  Larger batches mean fewer calls to `reduce`, which helps
+    /// when it is expensive (e.g. merging large structures).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch` is zero.
+    pub fn batched_reduce<R, ID, F, OP>(self, batch: usize, identity: ID, fold: F, reduce: OP) -> R
+    where
+        R: Send,
+        ID: Fn() -> R + Sync,
+        F: Fn(R, T::Item) -> R + Sync,
+        OP: Fn(R, R) -> R + Sync,
+    {
+        assert!(batch != 0, "batch size must be nonzero");
+
+        self.fold(
+            || (identity(), identity(), 0),
+            |(acc, current, n), item| {
+                let current = fold(current, item);
+                if n + 1 == batch {
+                    (reduce(acc, current), identity(), 0)
+                } else {
+                    (acc, current, n + 1)
+                }
+            },
+        )
+        .map(|(acc, current, _)| reduce(acc, current))
+        .reduce(&identity, &reduce)
+    }
+
+    /// Search for an item that matches a predicate, giving up at a deadline.
+    ///
+    /// Like [`any()`](ParallelIterator::any), every branch stops as soon as a match is found.  The
+    /// deadline is checked before testing each item, so every branch also stops soon after it
+    /// passes.
+    pub fn any_with_timeout<P>(self, p: P, deadline: Instant) -> SearchOutcome
+    where
+        P: Fn(&T::Item) -> bool + Sync,
+    {
+        let found = AtomicBool::new(false);
+        let timed_out = AtomicBool::new(false);
+
+        self.any(|item| {
+            if p(&item) {
+                found.store(true, Ordering::Relaxed);
+                true
+            } else if Instant::now() >= deadline {
+                timed_out.store(true, Ordering::Relaxed);
+                true
+            } else {
+                false
+            }
+        });
+
+        if found.into_inner() {
+            SearchOutcome::Found
+        } else if timed_out.into_inner() {
+            SearchOutcome::TimedOut
+        } else {
+            SearchOutcome::NotFound
+        }
+    }
+
+    /// Group the items into buckets by key.
+    ///
+    /// Each branch builds its own map, and the maps are merged by extending the vectors of
+    /// matching keys.  The items within each bucket are in no particular order.
+    pub fn collect_grouped<K, F>(self, key: F) -> HashMap<K, Vec<T::Item>>
+    where
+        K: Eq + Hash + Send,
+        F: Fn(&T::Item) -> K + Sync,
+    {
+        self.fold(HashMap::new, |mut map: HashMap<K, Vec<T::Item>>, item| {
+            map.entry(key(&item)).or_default().push(item);
+            map
+        })
+        .reduce(HashMap::new, |mut left, mut right| {
+            // Merge the smaller map into the larger one
+            if left.len() < right.len() {
+                std::mem::swap(&mut left, &mut right);
+            }
+            for (k, mut items) in right {
+                left.entry(k).or_default().append(&mut items);
+            }
+            left
+        })
+    }
+
+    /// Fold each branch, then merge the branch results in a balanced binary tree.
+    ///
+    /// The normal reduction follows the shape of the split tree, which can be lopsided: a branch
+    /// that consumes some items before splitting has to merge its partial result with everything
+    /// that was split off after it.  When merge cost grows with the size of the results (like
+    /// merging sorted vectors), that can be expensive.  This method instead collects the result
+    /// of every branch, and merges neighbouring pairs in parallel rounds, so that no result goes
+    /// through more than `⌈log₂(branches)⌉` merges.
+    ///
+    /// The tradeoff is that no merging can start until every branch is done, and all the branch
+    /// results are kept in memory at once.
+    pub fn fold_balanced<R, ID, F, M>(self, identity: ID, fold: F, merge: M) -> R
+    where
+        R: Send,
+        ID: Fn() -> R + Sync,
+        F: Fn(R, T::Item) -> R + Sync,
+        M: Fn(R, R) -> R + Sync,
+    {
+        let results: Vec<R> = self.fold(&identity, &fold).collect();
+        merge_balanced(results, &identity, &merge)
+    }
+
+    /// Fold each branch, then merge the results, picking the merge shape by result size.
+    ///
+    /// If the result type is bigger than the [threshold](Self::with_reduce_threshold), this is
+    /// [`fold_balanced()`](Self::fold_balanced).  Otherwise, the results are merged as the
+    /// branches complete, following the split tree.
+    pub fn fold_reduce<R, ID, F, M>(self, identity: ID, fold: F, merge: M) -> R
+    where
+        R: Send,
+        ID: Fn() -> R + Sync,
+        F: Fn(R, T::Item) -> R + Sync,
+        M: Fn(R, R) -> R + Sync,
+    {
+        if std::mem::size_of::<R>() > self.config.reduce_threshold {
+            self.fold_balanced(identity, fold, merge)
+        } else {
+            self.fold(&identity, &fold).reduce(&identity, &merge)
+        }
+    }
+
+    /// Run a fallible function on every item, with some state from a fallible initializer.
+    ///
+    /// Each branch calls `init` before processing its first item, so branches that don't produce
+    /// any items don't initialize anything.  If either `init` or `f` fails on any branch, every
+    /// branch stops and one of the errors is returned, though which one is not deterministic.
+    pub fn for_each_try_init<S, E, INIT, F>(self, init: INIT, f: F) -> Result<(), E>
+    where
+        E: Send,
+        INIT: Fn() -> Result<S, E> + Sync,
+        F: Fn(&mut S, T::Item) -> Result<(), E> + Sync,
+    {
+        self.try_for_each_init(
+            || None,
+            |state, item| {
+                let state = match state {
+                    Some(state) => state,
+                    None => state.insert(init()?),
+                };
+                f(state, item)
+            },
+        )
+    }
+
+    /// Collect the items into a sorted vector.
+    ///
+    /// This simply collects everything into one vector, then sorts it in parallel, so it costs
+    /// `O(n log n)` on top of the collection itself.
+    pub fn collect_sorted(self) -> Vec<T::Item>
+    where
+        T::Item: Ord,
+    {
+        let mut vec: Vec<_> = self.collect();
+        vec.par_sort_unstable();
+        vec
+    }
+
+    /// Reduce the items with a commutative operation.
+    ///
+    /// This gives the same result as [`reduce()`](ParallelIterator::reduce), as long as `op` is
+    /// both associative and commutative.  That frees the implementation to combine results in any
+    /// order: currently, each branch folds its items locally, then merges its result into a
+    /// shared accumulator as soon as it finishes, instead of waiting for its sibling in the split
+    /// tree.  The accumulator is locked during each merge, so `op` should be cheap.  For
+    /// primitive items, [`reduce_commutative_atomic()`](Self::reduce_commutative_atomic) avoids
+    /// the lock.
+    pub fn reduce_commutative<ID, OP>(self, identity: ID, op: OP) -> T::Item
+    where
+        ID: Fn() -> T::Item + Sync,
+        OP: Fn(T::Item, T::Item) -> T::Item + Sync,
+    {
+        let acc = Mutex::new(None);
+
+        self.fold(&identity, &op).for_each(|partial| {
+            let mut acc = acc.lock().unwrap();
+            let merged = match acc.take() {
+                Some(prev) => op(prev, partial),
+                None => partial,
+            };
+            *acc = Some(merged);
+        });
+
+        acc.into_inner().unwrap().unwrap_or_else(identity)
+    }
+
+    /// Reduce primitive items with a commutative operation, without locking.
+    ///
+    /// This works like [`reduce_commutative()`](Self::reduce_commutative), except the shared
+    /// accumulator is an atomic (see [AtomicItem]), and each branch merges its result into it
+    /// with a compare-and-swap loop.  Under contention, `op` may be called more than once per
+    /// merge, so it should be cheap and free of side effects.
+    pub fn reduce_commutative_atomic<ID, OP>(self, identity: ID, op: OP) -> T::Item
+    where
+        T::Item: AtomicItem,
+        ID: Fn() -> T::Item + Sync,
+        OP: Fn(T::Item, T::Item) -> T::Item + Sync,
+    {
+        let acc = T::Item::new_atomic(identity());
+
+        self.fold(&identity, &op).for_each(|partial| {
+            T::Item::update(&acc, |prev| op(prev, partial));
+        });
+
+        T::Item::into_value(acc)
+    }
+
+    /// Reduce the items into an existing accumulator.
+    ///
+    /// Each branch folds its items into a fresh [`Default`] accumulator with `fold`.  The branch
+    /// results are combined with `merge`, and finally merged into `acc`.
+    pub fn reduce_into<Acc, F, M>(self, acc: &mut Acc, fold: F, merge: M)
+    where
+        Acc: Default + Send,
+        F: Fn(&mut Acc, T::Item) + Sync,
+        M: Fn(&mut Acc, Acc) + Sync,
+    {
+        let result = self
+            .fold(Acc::default, |mut acc, item| {
+                fold(&mut acc, item);
+                acc
+            })
+            .reduce(Acc::default, |mut left, right| {
+                merge(&mut left, right);
+                left
+            });
+        merge(acc, result);
+    }
+
+    /// Find the greatest depth among the items, as reported by `f`.
+    ///
+    /// This is meant for searches whose items know how deep they are in the search tree, to find
+    /// out how deep the search actually went.  Returns zero if there are no items.
+    pub fn max_depth_by<F>(self, f: F) -> u32
+    where
+        F: Fn(&T::Item) -> u32 + Sync,
+    {
+        self.fold(|| 0, |depth, item| depth.max(f(&item)))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Compress runs of equal items into `(item, count)` pairs.
+    ///
+    /// Each branch encodes its own items, and when two branch results are combined, the last run
+    /// of the left one is merged with the first run of the right one if their items are equal.
+    /// The runs come out in split-tree order, as in
+    /// [`fold_ordered_per_branch()`](Self::fold_ordered_per_branch), so for a sorted Spliterator
+    /// that keeps the earlier items when it splits, the result is the same as encoding the items
+    /// sequentially.
+    pub fn run_length_encode(self) -> Vec<(T::Item, usize)>
+    where
+        T::Item: Eq,
+    {
+        self.ordered()
+            .fold(Vec::new, |mut runs: Vec<(T::Item, usize)>, item| {
+                match runs.last_mut() {
+                    Some((last, n)) if *last == item => *n += 1,
+                    _ => runs.push((item, 1)),
+                }
+                runs
+            })
+            .reduce(Vec::new, |mut left, right| {
+                let mut right = right.into_iter();
+                if let Some((first, m)) = right.next() {
+                    // Merge the runs that meet at the boundary
+                    match left.last_mut() {
+                        Some((last, n)) if *last == first => *n += m,
+                        _ => left.push((first, m)),
+                    }
+                }
+                left.extend(right);
+                left
+            })
+    }
+
+    /// Count how many items there are for each key.
+    ///
+    /// Each branch counts into its own map, and the maps are merged by summing the counts of
+    /// matching keys.  Like any [HashMap], the result iterates in no particular order.
+    pub fn count_by_key<K, F>(self, key: F) -> HashMap<K, usize>
+    where
+        K: Eq + Hash + Send,
+        F: Fn(&T::Item) -> K + Sync,
+    {
+        self.fold(HashMap::new, |mut map: HashMap<K, usize>, item| {
+            *map.entry(key(&item)).or_default() += 1;
+            map
+        })
+        .reduce(HashMap::new, |mut left, mut right| {
+            // Merge the smaller map into the larger one
+            if left.len() < right.len() {
+                std::mem::swap(&mut left, &mut right);
+            }
+            for (k, n) in right {
+                *left.entry(k).or_default() += n;
+            }
+            left
+        })
+    }
+
+    /// Collect the items into a [Vec], preallocated from the root's size hint.
+    ///
+    /// If the root Spliterator's [`size_hint()`](Iterator::size_hint) is exact, the vector is
+    /// allocated up front with exactly that capacity.  Otherwise, this is the same as
+    /// [`collect()`](ParallelIterator::collect).  Adapters that yield every item, like
+    /// [`with_item_counter()`](Self::with_item_counter), keep the hint exact, while ones that
+    /// may stop early, like [`with_cancel()`](Self::with_cancel), don't.
+    pub fn collect_vec(self) -> Vec<T::Item> {
+        let mut vec = match self.iter.size_hint() {
+            (lo, Some(hi)) if lo == hi => Vec::with_capacity(hi),
+            _ => Vec::new(),
+        };
+        vec.par_extend(self);
+        vec
+    }
+
+    /// Map each item and reduce the results, in a single fused pass.
+    ///
+    /// Each branch folds the mapped items directly into a local accumulator starting from
+    /// `identity()`, and the branch results are combined with the same `reduce` function, so
+    /// `reduce` should be associative.
+    pub fn map_reduce<R, ID, MF, RF>(self, identity: ID, map: MF, reduce: RF) -> R
+    where
+        R: Send,
+        ID: Fn() -> R + Sync,
+        MF: Fn(T::Item) -> R + Sync,
+        RF: Fn(R, R) -> R + Sync,
+    {
+        self.fold(&identity, |acc, item| reduce(acc, map(item)))
+            .reduce(&identity, &reduce)
+    }
+
+    /// Reduce the items bottom-up, following the shape of the split tree.
+    ///
+    /// Every item is turned into a leaf with `leaf()`.  Within a branch, the leaves are combined
+    /// from left to right, and whenever a branch splits, its result is combined with the result
+    /// of the half it split off, as `combine(own, split)`.  So the calls to `combine()` mirror the
+    /// splits that actually happened, which makes this useful for building tree-shaped results
+    /// like expression trees.  Returns `None` if there were no items at all.
+    pub fn reduce_tree<R, L, F>(self, leaf: L, combine: F) -> Option<R>
+    where
+        R: Send,
+        L: Fn(T::Item) -> R + Sync,
+        F: Fn(R, R) -> R + Sync,
+    {
+        self.fold(
+            || None,
+            |acc, item| {
+                let item = leaf(item);
+                Some(match acc {
+                    Some(acc) => combine(acc, item),
+                    None => item,
+                })
+            },
+        )
+        .reduce(
+            || None,
+            |left, right| match (left, right) {
+                (Some(left), Some(right)) => Some(combine(left, right)),
+                (left, right) => left.or(right),
+            },
+        )
+    }
+
+    /// Call `f` on each item, along with the branch that produced it and its index there.
+    ///
+    /// A branch here is a stretch of items between splits: whenever a branch splits, both halves
+    /// get new [BranchId]s and start counting from zero again.  So every branch has a unique ID,
+    /// and its items have contiguous indices starting from zero.  To keep the IDs unique,
+    /// branches stop splitting once they're [`BranchId::MAX_DEPTH`] splits deep.
+    pub fn for_each_with_location<F>(self, f: F)
+    where
+        F: Fn(BranchId, usize, T::Item) + Sync,
+    {
+        self.adapt(BranchLocation::new)
+            .for_each(|(id, i, item)| f(id, i, item));
+    }
+
+    /// Collect the items of each branch into a separate vector.
+    ///
+    /// A branch here is a stretch of items between splits, as in
+    /// [`for_each_with_location()`](Self::for_each_with_location), and branches that didn't
+    /// produce any items are left out.  How many vectors there are, and which items end up
+    /// together, depends entirely on how the work was split.
+    pub fn collect_branches(self) -> Vec<Vec<T::Item>> {
+        self.fold(Vec::new, |mut items, item| {
+            items.push(item);
+            items
+        })
+        .filter(|items| !items.is_empty())
+        .map(|items| vec![items])
+        .reduce(Vec::new, |mut left, mut right| {
+            left.append(&mut right);
+            left
+        })
+    }
+
+    /// Reduce the items in an order that depends only on the split tree.
+    ///
+    /// This is [`reduce()`](ParallelIterator::reduce) in [deterministic](Self::deterministic)
+    /// mode.  Rayon always combines branch results in split-tree order, so once the tree itself
+    /// doesn't depend on scheduling, an associative `op` gives the same result on every run with
+    /// the same number of threads, even if it isn't commutative.
+    pub fn reduce_deterministic<ID, OP>(self, identity: ID, op: OP) -> T::Item
+    where
+        ID: Fn() -> T::Item + Sync + Send,
+        OP: Fn(T::Item, T::Item) -> T::Item + Sync + Send,
+    {
+        self.deterministic().ordered().reduce(identity, op)
+    }
+
+    /// Consume every item, and report how long it took.
+    ///
+    /// Each branch notes which thread it ran on, so the stats also show how many threads
+    /// actually took part.
+    pub fn measure(self) -> RunStats {
+        let threads = Mutex::new(HashSet::new());
+        let start = Instant::now();
+
+        let items = self
+            .fold(
+                || {
+                    threads.lock().unwrap().insert(thread::current().id());
+                    0
+                },
+                |n, _| n + 1,
+            )
+            .sum();
+
+        RunStats {
+            items,
+            elapsed: start.elapsed(),
+            threads: threads.into_inner().unwrap().len(),
+        }
+    }
+
+    /// Write the items into a slice, in sequential order.
+    ///
+    /// This relies on the Spliterator knowing its exact length, and on every split keeping the
+    /// earlier items and handing off the later ones, like the range and vector spliterators do.
+    /// Each branch then owns the part of `out` that its items belong in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the Spliterator doesn't match the length of `out`, or if it runs
+    /// out of items before filling `out`.
+    pub fn collect_into_slice(self, out: &mut [T::Item])
+    where
+        T: ExactSizeIterator,
+    {
+        let len = out.len();
+        assert_eq!(self.iter.len(), len, "length mismatch");
+
+        let filled = self
+            .adapt(|iter| SliceFill::new(iter, out))
+            .map(|(slot, item)| *slot = item)
+            .count();
+        assert_eq!(filled, len, "ran out of items");
+    }
+
+    /// Get the `k` items with the largest keys, from largest to smallest.
+    ///
+    /// Each branch keeps the best `k` items it has seen in a bounded min-heap, and the heaps are
+    /// merged keeping the best `k` overall, so nothing close to the whole input is ever sorted.
+    /// Ties between equal keys are broken arbitrarily.
+    pub fn top_k_by_key<K, F>(self, k: usize, key: F) -> Vec<T::Item>
+    where
+        K: Ord + Send,
+        F: Fn(&T::Item) -> K + Sync,
+    {
+        type Heap<K, V> = BinaryHeap<Reverse<ByKey<K, V>>>;
+
+        let truncate = |heap: &mut Heap<K, T::Item>| {
+            while heap.len() > k {
+                heap.pop();
+            }
+        };
+
+        self.fold(Heap::new, |mut heap, item| {
+            heap.push(Reverse(ByKey(key(&item), item)));
+            truncate(&mut heap);
+            heap
+        })
+        .reduce(Heap::new, |mut left, mut right| {
+            // Merge the smaller heap into the larger one
+            if left.len() < right.len() {
+                std::mem::swap(&mut left, &mut right);
+            }
+            left.append(&mut right);
+            truncate(&mut left);
+            left
+        })
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(ByKey(_, item))| item)
+        .collect()
+    }
+
+    /// Call `f` on batches of up to `max_batch` items at a time.
+    ///
+    /// Each branch buffers the items it consumes, and hands them to `f` as a slice whenever the
+    /// buffer fills up.  Whatever is left over is flushed when the branch completes, so batch
+    /// sizes vary, and each branch's last batch is usually short.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_batch` is zero.
+    pub fn for_each_batch<F>(self, max_batch: usize, f: F)
+    where
+        F: Fn(&[T::Item]) + Sync,
+    {
+        assert!(max_batch != 0, "batch size must be nonzero");
+
+        self.fold(
+            || Vec::with_capacity(max_batch),
+            |mut batch, item| {
+                batch.push(item);
+                if batch.len() == max_batch {
+                    f(&batch);
+                    batch.clear();
+                }
+                batch
+            },
+        )
+        .for_each(|batch| {
+            if !batch.is_empty() {
+                f(&batch);
+            }
+        });
+    }
+
+    /// Send every item over a channel as soon as it's produced.
+    ///
+    /// This turns the parallel run into a streaming source: the receiving end sees items while
+    /// the other branches are still working, in no particular order.  If the receiver hangs up,
+    /// every branch stops at its next item, and the item that couldn't be sent is returned in the
+    /// error.
+    ///
+    /// This takes a [`std::sync::mpsc::Sender`] rather than a `crossbeam-channel` one, so it
+    /// doesn't need an extra dependency or feature.  Since Rust 1.67, the standard channel is
+    /// built on the same implementation as `crossbeam-channel`.
+    pub fn drive_to_sender(self, tx: Sender<T::Item>) -> Result<(), SendError<T::Item>> {
+        self.try_for_each_with(tx, |tx, item| tx.send(item))
+    }
+
+    /// Process the items in chunks of up to `chunk` items, with per-branch state.
+    ///
+    /// Each branch creates its state with `init()`, buffers its items, and hands them to `f`
+    /// along with the state whenever the buffer fills up, like
+    /// [`for_each_batch()`](Self::for_each_batch).  The states of all the branches are then
+    /// merged with `reduce`, which should be associative.  This is a good fit for vectorized
+    /// processing, where `f` works best on a whole slice at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk` is zero.
+    pub fn fold_chunks_with<S, INIT, F, R>(self, chunk: usize, init: INIT, f: F, reduce: R) -> S
+    where
+        S: Send,
+        INIT: Fn() -> S + Sync,
+        F: Fn(&mut S, &[T::Item]) + Sync,
+        R: Fn(S, S) -> S + Sync,
+    {
+        assert!(chunk != 0, "chunk size must be nonzero");
+
+        self.fold(
+            || (init(), Vec::with_capacity(chunk)),
+            |(mut state, mut buffer), item| {
+                buffer.push(item);
+                if buffer.len() == chunk {
+                    f(&mut state, &buffer);
+                    buffer.clear();
+                }
+                (state, buffer)
+            },
+        )
+        .map(|(mut state, buffer)| {
+            if !buffer.is_empty() {
+                f(&mut state, &buffer);
+            }
+            state
+        })
+        .reduce(&init, &reduce)
+    }
+
+    /// Fold each branch separately, returning the accumulators in split-tree order.
+    ///
+    /// Each branch folds its items in [`next()`](Iterator::next) order, starting from
+    /// `identity()`.  The accumulators are returned in the order of the split tree: a branch's
+    /// own items come first, then everything from its remaining half, then everything from the
+    /// half it split off.  For Spliterators that keep the earlier items when they split, that's
+    /// sequential order.
+    pub fn fold_ordered_per_branch<Acc, ID, F>(self, identity: ID, fold: F) -> Vec<Acc>
+    where
+        Acc: Send,
+        ID: Fn() -> Acc + Sync,
+        F: Fn(Acc, T::Item) -> Acc + Sync,
+    {
+        self.ordered()
+            .fold(&identity, &fold)
+            .map(|acc| vec![acc])
+            .reduce(Vec::new, |mut left, mut right| {
+                left.append(&mut right);
+                left
+            })
+    }
+
+    /// Collect each branch into its own vector, preallocated with capacity `cap`.
+    ///
+    /// This is like [`fold()`](ParallelIterator::fold) into [Vec]s, but each branch reserves
+    /// space for `cap` items up front, so branches with up to that many items never reallocate.
+    /// The tradeoff is memory: every branch allocates the full capacity, even if it ends up with
+    /// far fewer items, and there can be many more branches than threads.
+    pub fn fold_into_vecs_with_capacity(
+        self,
+        cap: usize,
+    ) -> impl ParallelIterator<Item = Vec<T::Item>> {
+        self.fold(
+            move || Vec::with_capacity(cap),
+            |mut vec, item| {
+                vec.push(item);
+                vec
+            },
+        )
+    }
+
+    /// Consume every item, separating the successes from the failures.
+    ///
+    /// Unlike collecting into a [Result], this doesn't stop at the first error, so every item is
+    /// processed and every error is reported.  Each branch collects into its own pair of vectors,
+    /// and the pairs are concatenated, so both outputs are in no particular order.
+    pub fn collect_partition<R, E>(self) -> (Vec<R>, Vec<E>)
+    where
+        T: Spliterator<Item = Result<R, E>>,
+        R: Send,
+        E: Send,
+    {
+        self.fold(
+            || (Vec::new(), Vec::new()),
+            |(mut oks, mut errs), item| {
+                match item {
+                    Ok(ok) => oks.push(ok),
+                    Err(err) => errs.push(err),
+                }
+                (oks, errs)
+            },
+        )
+        .reduce(
+            || (Vec::new(), Vec::new()),
+            |(mut oks, mut errs), (mut more_oks, mut more_errs)| {
+                oks.append(&mut more_oks);
+                errs.append(&mut more_errs);
+                (oks, errs)
+            },
+        )
+    }
+
+    /// Call `f` on each item until `pause` is set, then return the work that's left.
+    ///
+    /// Once the flag is set, each branch stops before its next item or split, and hands back
+    /// its Spliterator instead.  Together, these make up the frontier of the run: every item
+    /// either went to `f`, or will be produced by one of the returned Spliterators, and none of
+    /// them are shared.  The protocol is:
+    ///
+    /// 1. Set `pause`, e.g. from `f` itself or from another thread.
+    /// 2. Inspect the returned frontier, which is empty if the run finished before it noticed.
+    /// 3. Clear `pause`, and resume by running the frontier again, e.g. with
+    ///    [`par_split_fair()`](crate::par_split_fair) or by calling this method again on it.
+    pub fn peek_frontier<F>(self, pause: Arc<AtomicBool>, f: F) -> Vec<T>
+    where
+        F: Fn(T::Item) + Sync,
+    {
+        let frontier = Arc::new(Mutex::new(Vec::new()));
+        self.adapt(|iter| Pausable::new(iter, pause, frontier.clone()))
+            .for_each(&f);
+
+        let mut frontier = frontier.lock().unwrap();
+        std::mem::take(&mut *frontier)
+    }
+
+    /// Concatenate all the strings, in split-tree order.
+    ///
+    /// The pieces are joined in the same order as
+    /// [`fold_ordered_per_branch()`](Self::fold_ordered_per_branch), so for a Spliterator that
+    /// keeps the earlier items when it splits, this is the same as concatenating them
+    /// sequentially.  When two partial results are joined, whichever one already has enough
+    /// spare capacity is reused, to avoid reallocating where possible.
+    pub fn concat_strings<S>(self) -> String
+    where
+        T: Spliterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.ordered()
+            .fold(String::new, |mut acc, item| {
+                acc.push_str(item.as_ref());
+                acc
+            })
+            .reduce(String::new, |mut left, mut right| {
+                if left.capacity() - left.len() < right.len()
+                    && right.capacity() - right.len() >= left.len()
+                {
+                    right.insert_str(0, &left);
+                    right
+                } else {
+                    left.push_str(&right);
+                    left
+                }
+            })
+    }
+
+    /// Concatenate all the vectors, in split-tree order.
+    ///
+    /// This is like [`concat_strings()`](Self::concat_strings), but for vectors.
+    pub fn concat_vecs<U>(self) -> Vec<U>
+    where
+        T: Spliterator<Item = Vec<U>>,
+        U: Send,
+    {
+        self.ordered()
+            .fold(Vec::new, |mut acc, mut item| {
+                if acc.is_empty() && item.capacity() > acc.capacity() {
+                    item
+                } else {
+                    acc.append(&mut item);
+                    acc
+                }
+            })
+            .reduce(Vec::new, |mut left, mut right| {
+                if left.capacity() - left.len() < right.len()
+                    && right.capacity() - right.len() >= left.len()
+                {
+                    right.splice(0..0, left);
+                    right
+                } else {
+                    left.append(&mut right);
+                    left
+                }
+            })
+    }
+
+    /// Sort each item into one of two vectors, or drop it, according to `f`.
+    ///
+    /// This is like [`partition()`](ParallelIterator::partition), but it can also throw items
+    /// away with [`Route::Drop`], and it appends to existing vectors rather than creating new
+    /// ones.  Each branch routes into its own pair of vectors, and the pairs are concatenated, so
+    /// the new items are in no particular order.
+    pub fn route_into<F>(self, left: &mut Vec<T::Item>, right: &mut Vec<T::Item>, f: F)
+    where
+        F: Fn(&T::Item) -> Route + Sync,
+    {
+        let (mut lefts, mut rights) = self
+            .fold(
+                || (Vec::new(), Vec::new()),
+                |(mut lefts, mut rights), item| {
+                    match f(&item) {
+                        Route::Left => lefts.push(item),
+                        Route::Right => rights.push(item),
+                        Route::Drop => {}
+                    }
+                    (lefts, rights)
+                },
+            )
+            .reduce(
+                || (Vec::new(), Vec::new()),
+                |(mut lefts, mut rights), (mut more_lefts, mut more_rights)| {
+                    lefts.append(&mut more_lefts);
+                    rights.append(&mut more_rights);
+                    (lefts, rights)
+                },
+            );
+
+        left.append(&mut lefts);
+        right.append(&mut rights);
+    }
+}
+
+/// Estimate the number of items left in an iterator from its size hint.
+fn estimated_len<T: Iterator>(iter: &T) -> usize {
+    match iter.size_hint() {
+        (_, Some(hi)) => hi,
+        (lo, None) => lo,
+    }
+}
+
+/// Merge some results in a balanced binary tree.
+fn merge_balanced<R, ID, M>(mut results: Vec<R>, identity: &ID, merge: &M) -> R
+where
+    R: Send,
+    ID: Fn() -> R + Sync,
+    M: Fn(R, R) -> R + Sync,
+{
+    match results.len() {
+        0 => identity(),
+        1 => results.pop().unwrap(),
+        len => {
+            let right = results.split_off(len / 2);
+            let (left, right) = join(
+                || merge_balanced(results, identity, merge),
+                || merge_balanced(right, identity, merge),
+            );
+            merge(left, right)
+        }
+    }
+}
+
+/// A value ordered only by its key.
+struct ByKey<K, V>(K, V);
+
+impl<K: PartialEq, V> PartialEq for ByKey<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K: Eq, V> Eq for ByKey<K, V> {}
+
+impl<K: Ord, V> PartialOrd for ByKey<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, V> Ord for ByKey<K, V> {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.0.cmp(&other.0)
+    }
+}
+
+/// The result of a search with a deadline.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SearchOutcome {
+    /// A match was found.
+    Found,
+    /// Every item was searched, and none matched.
+    NotFound,
+    /// The deadline passed before a match was found.
+    TimedOut,
+}
+
+/// Where to send an item, for [`ParSpliter::route_into()`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Route {
+    /// Append the item to the left vector.
+    Left,
+    /// Append the item to the right vector.
+    Right,
+    /// Drop the item.
+    Drop,
+}
+
+/// Statistics about a run, from [`ParSpliter::measure()`].
+#[derive(Clone, Copy, Debug)]
+pub struct RunStats {
+    /// The number of items consumed.
+    pub items: usize,
+    /// The total wall-clock time of the run.
+    pub elapsed: Duration,
+    /// The number of distinct threads that ran at least one branch.
+    pub threads: usize,
+}
+
+impl RunStats {
+    /// Get the average throughput, in items per second.
+    ///
+    /// Returns zero if no time elapsed at all, rather than dividing by zero.
+    pub fn items_per_sec(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            0.0
+        } else {
+            self.items as f64 / self.elapsed.as_secs_f64()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All the numbers from 1 to 2^16 - 1, enumerated as a binary tree.
+    #[derive(Clone)]
+    struct AllNumbers {
+        stack: Vec<u32>,
+    }
+
+    impl AllNumbers {
+        const COUNT: usize = (1 << 16) - 1;
+
+        fn new() -> Self {
+            Self { stack: vec![1] }
+        }
+    }
+
+    impl Iterator for AllNumbers {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if let Some(n) = self.stack.pop() {
+                if n < 1 << 15 {
+                    self.stack.push(2 * n);
+                    self.stack.push(2 * n + 1);
+                }
+                Some(n)
+            } else {
+                None
+            }
+        }
+    }
+
+    impl Spliterator for AllNumbers {
+        fn split(&mut self) -> Option<Self> {
+            let len = self.stack.len();
+            if len >= 2 {
+                let split = self.stack.split_off(len / 2);
+                Some(Self { stack: split })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Splits off one item at a time, making a maximally deep split tree.
+    struct Linear(std::ops::Range<u32>);
+
+    impl Iterator for Linear {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            self.0.next()
+        }
+    }
+
+    impl Spliterator for Linear {
+        fn split(&mut self) -> Option<Self> {
+            if self.0.len() < 2 {
+                return None;
+            }
+            let last = self.0.next_back()?;
+            Some(Self(last..(last + 1)))
+        }
+    }
+
+    /// A range of numbers with configurable splitting.
+    #[derive(Clone, Debug)]
+    struct TestRange {
+        range: std::ops::Range<u32>,
+        /// Split off `1/divisor` of the items from the end, or panic if it's zero.
+        divisor: u32,
+        /// The number of successful splits, shared by every branch.
+        splits: Arc<AtomicUsize>,
+        /// While set, [`should_split()`](Spliterator::should_split) returns false.
+        veto: Arc<AtomicBool>,
+    }
+
+    impl TestRange {
+        /// Create a range that splits in half.
+        fn new(range: std::ops::Range<u32>) -> Self {
+            Self {
+                range,
+                divisor: 2,
+                splits: Arc::new(AtomicUsize::new(0)),
+                veto: Arc::new(AtomicBool::new(false)),
+            }
+        }
+
+        /// Split off `1/divisor` of the items at a time, or panic on any split if it's zero.
+        fn with_divisor(mut self, divisor: u32) -> Self {
+            self.divisor = divisor;
+            self
+        }
+    }
+
+    impl Iterator for TestRange {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            self.range.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.range.size_hint()
+        }
+    }
+
+    impl Spliterator for TestRange {
+        fn split(&mut self) -> Option<Self> {
+            assert!(self.divisor > 0, "split() called on an unsplittable range");
+            assert!(!self.veto.load(Ordering::Relaxed));
+
+            let len = self.range.len() as u32 / self.divisor;
+            if len == 0 {
+                return None;
+            }
+            self.splits.fetch_add(1, Ordering::Relaxed);
+
+            let mid = self.range.end - len;
+            let range = mid..self.range.end;
+            self.range.end = mid;
+            Some(Self {
+                range,
+                ..self.clone()
+            })
+        }
+
+        fn max_items_per_branch(&self) -> Option<usize> {
+            Some(self.range.len())
+        }
+
+        fn should_split(&self) -> bool {
+            !self.veto.load(Ordering::Relaxed)
+        }
+    }
+
+    /// Count the branches of a run, since each one folds into its own accumulator.
+    fn count_branches(iter: impl ParallelIterator) -> usize {
+        iter.fold(|| (), |(), _| ()).count()
+    }
+
+    #[test]
+    fn test_par_split() {
+        assert_eq!(AllNumbers::new().count(), AllNumbers::COUNT);
+        assert_eq!(AllNumbers::new().par_split().count(), AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_will_parallelize() {
+        use rayon::{ThreadPool, ThreadPoolBuilder};
+
+        let check = |pool: &ThreadPool, iter: ParSpliter<AllNumbers>| {
+            pool.install(|| iter.will_parallelize())
+        };
+
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        assert!(!check(&pool, AllNumbers::new().par_split()));
+        assert!(!check(
+            &pool,
+            AllNumbers::new().par_split().single_thread_mode()
+        ));
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        assert!(check(&pool, AllNumbers::new().par_split()));
+        assert!(check(
+            &pool,
+            AllNumbers::new().par_split().single_thread_mode()
+        ));
+        assert!(!check(&pool, AllNumbers::new().par_split().sequential()));
+    }
+
+    #[test]
+    fn test_auto_granularity() {
+        let count = AllNumbers::new()
+            .par_split()
+            .with_auto_granularity()
+            .count();
+        assert_eq!(count, AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_min_len() {
+        let iter = TestRange::new(0..100_000);
+        let splits = iter.splits.clone();
+        assert_eq!(iter.par_split().count(), 100_000);
+        assert!(splits.load(Ordering::Relaxed) > 0);
+
+        let iter = TestRange::new(0..100_000);
+        let splits = iter.splits.clone();
+        assert_eq!(iter.par_split().with_min_len(1_000_000).count(), 100_000);
+        assert_eq!(splits.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_max_recursion() {
+        let count = Linear(0..100_000).par_split().with_max_recursion(3).count();
+        assert_eq!(count, 100_000);
+
+        let mut items: Vec<_> = Linear(0..1000).par_split().with_max_recursion(0).collect();
+        items.sort_unstable();
+        assert_eq!(items, (0..1000).collect::<Vec<_>>());
+
+        // With a split budget that doesn't halve, this would recurse once per item without the
+        // work queue, which is more than enough to overflow the stack
+        let count = Linear(0..1_000_000)
+            .par_split()
+            .with_split_token_bucket(0.0, usize::MAX)
+            .with_max_recursion(16)
+            .count();
+        assert_eq!(count, 1_000_000);
+    }
+
+    #[test]
+    fn test_sequential() {
+        use std::collections::HashSet;
+        use std::sync::Mutex;
+        use std::thread;
+
+        assert_eq!(
+            AllNumbers::new().par_split().sequential().count(),
+            AllNumbers::new().par_split().count()
+        );
+
+        let mut seq: Vec<_> = AllNumbers::new().par_split().sequential().collect();
+        let mut par: Vec<_> = AllNumbers::new().par_split().collect();
+        seq.sort_unstable();
+        par.sort_unstable();
+        assert_eq!(seq, par);
+
+        let threads = Mutex::new(HashSet::new());
+        AllNumbers::new().par_split().sequential().for_each(|_| {
+            threads.lock().unwrap().insert(thread::current().id());
+        });
+        assert_eq!(threads.into_inner().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_run_twice() {
+        let (count, items) = AllNumbers::new()
+            .par_split()
+            .run_twice(|iter| iter.count(), |iter| iter.collect::<Vec<_>>());
+        assert_eq!(count, AllNumbers::COUNT);
+        assert_eq!(items.len(), count);
+    }
+
+    #[test]
+    fn test_count_matching() {
+        let even = |n: &u32| n.is_multiple_of(2);
+        let expected = AllNumbers::new().filter(even).count();
+        assert_eq!(AllNumbers::new().par_split().count_matching(even), expected);
+    }
+
+    #[test]
+    fn test_find_any_or() {
+        let found = AllNumbers::new()
+            .par_split()
+            .find_any_or(0, |n| n % 1000 == 999);
+        assert_eq!(found % 1000, 999);
+
+        let missing = AllNumbers::new()
+            .par_split()
+            .find_any_or(0, |&n| n > 1 << 16);
+        assert_eq!(missing, 0);
+    }
+
+    #[test]
+    fn test_sum_product_spliter() {
+        let expected: u64 = (0..100_000u64).sum();
+        assert_eq!(
+            RangeInclusiveSpliter::from(0..=99_999u64)
+                .par_split()
+                .sum_spliter(),
+            expected
+        );
+
+        assert_eq!(
+            RangeInclusiveSpliter::from(1..=20u64)
+                .par_split()
+                .product_spliter(),
+            (1..21).product::<u64>()
+        );
+        assert_eq!(
+            VecSpliter::from(Vec::<u64>::new())
+                .par_split()
+                .sum_spliter(),
+            0
+        );
+        assert_eq!(
+            VecSpliter::from(Vec::<u64>::new())
+                .par_split()
+                .product_spliter(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_collect_concat() {
+        let vecs: Vec<Vec<u32>> = (0..300).map(|n| (0..n).collect()).collect();
+
+        let mut expected: Vec<_> = vecs.iter().flatten().copied().collect();
+        expected.sort_unstable();
+
+        let mut actual = VecSpliter::new(vecs).par_split().collect_concat();
+        actual.sort_unstable();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_last_any() {
+        let last = AllNumbers::new().par_split().last_any();
+        assert!(matches!(last, Some(1..=0xFFFF)));
+
+        assert_eq!(
+            VecSpliter::from(Vec::<u32>::new()).par_split().last_any(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_batched_reduce() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let expected: u64 = AllNumbers::new().map(u64::from).sum();
+
+        let combines = |batch| {
+            let count = AtomicUsize::new(0);
+            let sum = AllNumbers::new().par_split().batched_reduce(
+                batch,
+                || 0,
+                |acc, n| acc + u64::from(n),
+                |a, b| {
+                    count.fetch_add(1, Ordering::Relaxed);
+                    a + b
+                },
+            );
+            assert_eq!(sum, expected);
+            count.into_inner()
+        };
+
+        let small = combines(1);
+        let large = combines(1000);
+        assert!(small >= AllNumbers::COUNT);
+        assert!(large < small / 100);
+    }
+
+    #[test]
+    fn test_any_with_timeout() {
+        use std::time::Duration;
+
+        let deadline = || Instant::now() + Duration::from_secs(60);
+
+        let outcome = AllNumbers::new()
+            .par_split()
+            .any_with_timeout(|&n| n == 1234, deadline());
+        assert_eq!(outcome, SearchOutcome::Found);
+
+        let outcome = AllNumbers::new()
+            .par_split()
+            .any_with_timeout(|&n| n == 0, deadline());
+        assert_eq!(outcome, SearchOutcome::NotFound);
+
+        let outcome = RangeInclusiveSpliter::new(0, u64::MAX)
+            .par_split()
+            .any_with_timeout(|_| false, Instant::now() + Duration::from_millis(50));
+        assert_eq!(outcome, SearchOutcome::TimedOut);
+    }
+
+    #[test]
+    fn test_collect_grouped() {
+        let mut groups = AllNumbers::new().par_split().collect_grouped(|n| n % 3);
+        assert_eq!(groups.len(), 3);
+
+        for (k, items) in &mut groups {
+            items.sort_unstable();
+            let expected: Vec<_> = (1..=AllNumbers::COUNT as u32)
+                .filter(|n| n % 3 == *k)
+                .collect();
+            assert_eq!(*items, expected);
+        }
+    }
+
+    #[test]
+    fn test_fold_balanced() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// A sorted vector, and the depth of the merge tree that built it.
+        type Sorted = (Vec<u32>, u32);
+
+        let merge = |(a, m): Sorted, (b, n): Sorted| {
+            let mut merged = Vec::with_capacity(a.len() + b.len());
+            let (mut a, mut b) = (a.into_iter().peekable(), b.into_iter().peekable());
+            while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+                if x <= y {
+                    merged.extend(a.next());
+                } else {
+                    merged.extend(b.next());
+                }
+            }
+            merged.extend(a);
+            merged.extend(b);
+            (merged, m.max(n) + 1)
+        };
+
+        let leaves = AtomicUsize::new(0);
+        let (sorted, depth) = AllNumbers::new().par_split().fold_balanced(
+            || {
+                leaves.fetch_add(1, Ordering::Relaxed);
+                (Vec::new(), 0)
+            },
+            |(mut vec, depth), n| {
+                let i = vec.partition_point(|&m| m < n);
+                vec.insert(i, n);
+                (vec, depth)
+            },
+            merge,
+        );
+
+        assert_eq!(sorted, (1..=AllNumbers::COUNT as u32).collect::<Vec<_>>());
+        let leaves = leaves.into_inner();
+        assert!(depth <= leaves.next_power_of_two().ilog2());
+    }
+
+    #[test]
+    fn test_for_each_try_init() {
+        let inits = AtomicUsize::new(0);
+        let init = || match inits.fetch_add(1, Ordering::Relaxed) {
+            2 => Err(0),
+            _ => Ok(Vec::new()),
+        };
+        let push = |vec: &mut Vec<u32>, n| {
+            vec.push(n);
+            Ok(())
+        };
+
+        let result = AllNumbers::new()
+            .par_split()
+            .sequential()
+            .for_each_try_init(init, push);
+        assert_eq!(result, Ok(()));
+        assert_eq!(inits.load(Ordering::Relaxed), 1);
+
+        let result = AllNumbers::new()
+            .par_split()
+            .for_each_try_init(|| Err::<(), _>(0), |_, _| Ok(()));
+        assert_eq!(result, Err(0));
+
+        let result = AllNumbers::new()
+            .par_split()
+            .for_each_try_init(|| Ok(()), |_, n| if n == 1234 { Err(n) } else { Ok(()) });
+        assert_eq!(result, Err(1234));
+    }
+
+    #[test]
+    fn test_collect_sorted() {
+        let sorted = AllNumbers::new().par_split().collect_sorted();
+        assert_eq!(sorted, (1..=AllNumbers::COUNT as u32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_reduce_commutative() {
+        let sum = RangeInclusiveSpliter::from(0..=99_999u64)
+            .par_split()
+            .reduce_commutative(|| 0, |a, b| a + b);
+        assert_eq!(sum, (0..100_000).sum::<u64>());
+
+        let xor = AllNumbers::new()
+            .par_split()
+            .reduce_commutative(|| 0, |a, b| a ^ b);
+        assert_eq!(xor, AllNumbers::new().fold(0, |a, b| a ^ b));
+
+        let max = VecSpliter::from(Vec::<u32>::new())
+            .par_split()
+            .reduce_commutative(|| 42, u32::max);
+        assert_eq!(max, 42);
+    }
+
+    #[test]
+    fn test_reduce_commutative_atomic() {
+        let sum = RangeInclusiveSpliter::from(0..=99_999u64)
+            .par_split()
+            .reduce_commutative_atomic(|| 0, |a, b| a + b);
+        assert_eq!(sum, (0..100_000).sum::<u64>());
+
+        let xor = AllNumbers::new()
+            .par_split()
+            .reduce_commutative_atomic(|| 0, |a, b| a ^ b);
+        assert_eq!(xor, AllNumbers::new().fold(0, |a, b| a ^ b));
+
+        let min = RangeInclusiveSpliter::from(-1000..=999i32)
+            .par_split()
+            .reduce_commutative_atomic(|| i32::MAX, i32::min);
+        assert_eq!(min, -1000);
+
+        let max = VecSpliter::from(Vec::<u32>::new())
+            .par_split()
+            .reduce_commutative_atomic(|| 42, u32::max);
+        assert_eq!(max, 42);
+    }
+
+    #[test]
+    fn test_reduce_into() {
+        use std::collections::HashMap;
+
+        type Histogram = HashMap<u32, usize>;
+
+        let fold = |hist: &mut Histogram, n| *hist.entry(n % 10).or_default() += 1;
+        let merge = |hist: &mut Histogram, other: Histogram| {
+            for (k, v) in other {
+                *hist.entry(k).or_default() += v;
+            }
+        };
+
+        let mut expected = Histogram::new();
+        expected.insert(42, 1);
+        AllNumbers::new().for_each(|n| fold(&mut expected, n));
+
+        let mut actual = Histogram::new();
+        actual.insert(42, 1);
+        AllNumbers::new()
+            .par_split()
+            .reduce_into(&mut actual, fold, merge);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_find_map_any() {
+        let found = AllNumbers::new()
+            .par_split()
+            .find_map_any(|n| (n % 1000 == 999).then(|| n.to_string()));
+        assert!(found.unwrap().ends_with("999"));
+
+        let missing = AllNumbers::new()
+            .par_split()
+            .find_map_any(|n| (n == 0).then_some(n));
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let expected = AllNumbers::new().min().zip(AllNumbers::new().max());
+        assert_eq!(AllNumbers::new().par_split().min_max(), expected);
+        assert_eq!(expected, Some((1, AllNumbers::COUNT as u32)));
+
+        assert_eq!(
+            VecSpliter::from(Vec::<u32>::new()).par_split().min_max(),
+            None
+        );
+        assert_eq!(
+            RangeInclusiveSpliter::new(7u32, 7).par_split().min_max(),
+            Some((7, 7))
+        );
+    }
+
+    #[test]
+    fn test_retain_into() {
+        let mut evens = vec![0];
+        let odds = AllNumbers::new()
+            .par_split()
+            .retain_into(&mut evens, |n| n % 2 == 0);
+        assert_eq!(odds, AllNumbers::new().filter(|n| n % 2 == 1).count());
+
+        evens.sort_unstable();
+        let expected: Vec<_> = (0..=AllNumbers::COUNT as u32).step_by(2).collect();
+        assert_eq!(evens, expected);
+    }
+
+    #[test]
+    fn test_flat_map_iter() {
+        let expected: usize = (0..1000).sum();
+        let count = RangeInclusiveSpliter::from(0..=999)
+            .par_split()
+            .flat_map_iter(|n| 0..n)
+            .count();
+        assert_eq!(count, expected);
+    }
+
+    #[test]
+    fn test_limit_split() {
+        let items: Vec<_> = AllNumbers::new().par_split().limit_split(1000).collect();
+        assert_eq!(items.len(), 1000);
+
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), items.len());
+
+        let count = AllNumbers::new()
+            .par_split()
+            .limit_split(usize::MAX)
+            .count();
+        assert_eq!(count, AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_try_for_each_with() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let send = |tx: &mut std::sync::mpsc::Sender<u32>, n| {
+            if n == 1234 {
+                Err(n)
+            } else {
+                tx.send(n).map_err(|_| 0)
+            }
+        };
+
+        let result = AllNumbers::new()
+            .par_split()
+            .try_for_each_with(tx.clone(), send);
+        assert_eq!(result, Err(1234));
+
+        let result = RangeInclusiveSpliter::from(0..=1233u32)
+            .par_split()
+            .try_for_each_with(tx, send);
+        assert_eq!(result, Ok(()));
+        assert!(rx.into_iter().count() >= 1234);
+    }
+
+    #[test]
+    fn test_sample_every_branch() {
+        let all = AllNumbers::new().par_split().sample_every_branch(1).count();
+        assert_eq!(all, AllNumbers::COUNT);
+
+        let few = AllNumbers::new()
+            .par_split()
+            .sample_every_branch(1000)
+            .count();
+        assert!(few > 0);
+        assert!(few < AllNumbers::COUNT / 10);
+    }
+
+    #[test]
+    fn test_branch_path() {
+        use Side::*;
+
+        let mut left = BranchPath::new(AllNumbers::new());
+        assert_eq!(left.next(), Some((vec![], 1)));
+
+        let mut right = left.split().unwrap();
+        assert_eq!(left.next().unwrap().0, [Left]);
+        assert_eq!(right.next().unwrap().0, [Right]);
+
+        let mut right_right = right.split().unwrap();
+        assert_eq!(right.next().unwrap().0, [Right, Left]);
+        assert_eq!(right_right.next().unwrap().0, [Right, Right]);
+
+        let mut items: Vec<_> = AllNumbers::new()
+            .par_split()
+            .with_branch_path()
+            .map(|(_, n)| n)
+            .collect();
+        items.sort_unstable();
+        assert_eq!(items, (1..=AllNumbers::COUNT as u32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_dedup_branch() {
+        let runs = AllNumbers::new().flat_map(|n| std::iter::repeat_n(n / 3, 1 + n as usize % 4));
+        let mut expected: Vec<_> = runs.clone().collect();
+        expected.dedup();
+
+        let actual: Vec<_> = DedupBranch::new(runs).collect();
+        assert_eq!(actual, expected);
+
+        let count = AllNumbers::new().par_split().dedup_branch().count();
+        assert_eq!(count, AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_enumerate_branch() {
+        let indices: Vec<_> = EnumerateBranch::new(AllNumbers::new())
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(indices, (0..AllNumbers::COUNT).collect::<Vec<_>>());
+
+        let mut items: Vec<_> = AllNumbers::new()
+            .par_split()
+            .enumerate_branch()
+            .inspect(|&(i, _)| assert!(i < AllNumbers::COUNT))
+            .map(|(_, n)| n)
+            .collect();
+        items.sort_unstable();
+        assert_eq!(items, (1..=AllNumbers::COUNT as u32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_with_cancel() {
+        use std::sync::atomic::Ordering;
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let canceller = {
+            let flag = flag.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                flag.store(true, Ordering::Relaxed);
+                Instant::now()
+            })
+        };
+
+        // This would take forever if it weren't cancelled
+        let count = RangeInclusiveSpliter::new(0, u64::MAX)
+            .par_split()
+            .with_cancel(flag)
+            .count();
+        let returned = Instant::now();
+        assert!(count < u64::MAX as usize);
+
+        // The branches should notice the flag promptly
+        let cancelled = canceller.join().unwrap();
+        let elapsed = returned.saturating_duration_since(cancelled);
+        assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+    }
+
+    #[test]
+    fn test_rev_branch() {
+        let items: Vec<u32> = (0..10).collect();
+        let rev: Vec<_> = RevBranch::new(items.iter()).copied().collect();
+        assert_eq!(rev, [9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+
+        let mut left = RevBranch::new(items.iter());
+        let right = left.split().unwrap();
+        assert_eq!(left.copied().collect::<Vec<_>>(), [4, 3, 2, 1, 0]);
+        assert_eq!(right.copied().collect::<Vec<_>>(), [9, 8, 7, 6, 5]);
+
+        let items: Vec<u32> = (0..10_000).collect();
+        let mut actual: Vec<_> = items.iter().par_split().rev_branch().copied().collect();
+        actual.sort_unstable();
+        assert_eq!(actual, items);
+    }
+
+    #[test]
+    fn test_with_progress() {
+        use std::sync::atomic::Ordering;
+
+        let counter = Arc::new(AtomicU64::new(0));
+        let count = AllNumbers::new()
+            .par_split()
+            .with_progress(counter.clone())
+            .count();
+        assert_eq!(count, AllNumbers::COUNT);
+        assert_eq!(counter.load(Ordering::Relaxed), count as u64);
+    }
+
+    #[test]
+    fn test_with_yield_every() {
+        let count = AllNumbers::new().par_split().with_yield_every(3).count();
+        assert_eq!(count, AllNumbers::COUNT);
+
+        let count = AllNumbers::new().par_split().with_yield_every(0).count();
+        assert_eq!(count, AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_with_item_counter() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let count = AllNumbers::new()
+            .par_split()
+            .with_item_counter(counter.clone())
+            .count();
+        assert_eq!(counter.load(Ordering::Relaxed), count);
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        AllNumbers::new()
+            .par_split()
+            .with_item_counter(counter.clone())
+            .find_any(|&n| n == 2)
+            .unwrap();
+        assert!(counter.load(Ordering::Relaxed) < AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_for_each_with() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        AllNumbers::new()
+            .par_split()
+            .for_each_with(tx, |tx, n| tx.send(n).unwrap());
+
+        let mut actual: Vec<_> = rx.into_iter().collect();
+        actual.sort_unstable();
+
+        let mut expected: Vec<_> = AllNumbers::new().collect();
+        expected.sort_unstable();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_debug_trace() {
+        use rayon::ThreadPoolBuilder;
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let (count, events) = pool.install(|| {
+            AllNumbers::new()
+                .par_split()
+                .debug_trace(|iter| iter.count())
+        });
+        assert_eq!(count, AllNumbers::COUNT);
+        assert!(!events.is_empty());
+        assert!(events.windows(2).all(|w| w[0].time <= w[1].time));
+
+        let mut consumed = 0;
+        let mut splits = 0;
+        let mut completes = 0;
+        for event in &events {
+            match event.kind {
+                TraceKind::Split => splits += 1,
+                TraceKind::StealReset => {}
+                TraceKind::Consume(n) => consumed += n,
+                TraceKind::Complete => completes += 1,
+            }
+        }
+
+        // Every item is consumed exactly once, and every branch either splits or completes
+        assert_eq!(consumed, count);
+        assert_eq!(completes, splits + 1);
+    }
+
+    #[test]
+    fn test_count_by_key() {
+        let actual = AllNumbers::new().par_split().count_by_key(|n| n % 5);
+
+        let mut expected = HashMap::new();
+        for n in AllNumbers::new() {
+            *expected.entry(n % 5).or_default() += 1;
+        }
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_prime() {
+        /// Count the branches we can split into without consuming anything.
+        fn branches<T: Spliterator>(mut iter: T) -> usize {
+            if let Some(split) = iter.split() {
+                branches(iter) + branches(split)
+            } else {
+                1
+            }
+        }
+
+        assert_eq!(branches(AllNumbers::new()), 1);
+        assert!(branches(AllNumbers::new().par_split().prime(8).iter) >= 8);
+
+        let mut primed: Vec<_> = AllNumbers::new().par_split().prime(8).collect();
+        primed.sort_unstable();
+        let expected: Vec<_> = (1..=AllNumbers::COUNT as u32).collect();
+        assert_eq!(primed, expected);
+    }
+
+    #[test]
+    fn test_while_some_branch() {
+        let items = vec![Some(1), Some(2), None, Some(3)];
+        let actual: Vec<_> = VecSpliter::from(items)
+            .par_split()
+            .sequential()
+            .while_some_branch()
+            .collect();
+        assert_eq!(actual, [1, 2]);
+
+        let items: Vec<_> = (0..1000).map(Some).collect();
+        let count = VecSpliter::from(items)
+            .par_split()
+            .while_some_branch()
+            .count();
+        assert_eq!(count, 1000);
+    }
+
+    #[cfg(feature = "numa")]
+    #[test]
+    fn test_with_numa_affinity() {
+        use rayon::ThreadPoolBuilder;
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        #[cfg(target_os = "linux")]
+        let before = pool.broadcast(|_| numa::current_cpus());
+
+        let count = pool.install(|| AllNumbers::new().par_split().with_numa_affinity().count());
+        assert_eq!(count, AllNumbers::COUNT);
+
+        // The pins don't outlive the run
+        #[cfg(target_os = "linux")]
+        assert_eq!(pool.broadcast(|_| numa::current_cpus()), before);
+    }
+
+    #[test]
+    fn test_collect_vec() {
+        let items: Vec<_> = (0..10_000).collect();
+        let mut actual = VecSpliter::from(items.clone()).par_split().collect_vec();
+        assert_eq!(actual.capacity(), items.len());
+        actual.sort_unstable();
+        assert_eq!(actual, items);
+
+        // The hint survives the adapters
+        let actual = VecSpliter::from(items.clone())
+            .par_split()
+            .with_item_counter(Arc::new(AtomicUsize::new(0)))
+            .enumerate_branch()
+            .collect_vec();
+        assert_eq!(actual.capacity(), items.len());
+
+        let mut actual = AllNumbers::new().par_split().collect_vec();
+        actual.sort_unstable();
+        let expected: Vec<_> = (1..=AllNumbers::COUNT as u32).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_map_reduce() {
+        let actual = AllNumbers::new().par_split().map_reduce(
+            || 0,
+            |n| u64::from(n) * u64::from(n),
+            |a, b| a + b,
+        );
+        let expected: u64 = AllNumbers::new().map(|n| u64::from(n) * u64::from(n)).sum();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_with_split_token_bucket() {
+        use rayon::ThreadPoolBuilder;
+
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let fixed = pool.install(|| count_branches(AllNumbers::new().par_split()));
+        assert!(fixed <= 3);
+
+        let capacity = 4;
+        let bucket = pool.install(|| {
+            count_branches(
+                AllNumbers::new()
+                    .par_split()
+                    .with_split_token_bucket(1e6, capacity),
+            )
+        });
+        assert!(bucket > 2 * capacity + 1);
+
+        let count = AllNumbers::new()
+            .par_split()
+            .with_split_token_bucket(1e6, capacity)
+            .count();
+        assert_eq!(count, AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_for_each_with_location() {
+        fn check<T: Spliterator + Send>(iter: ParSpliter<T>, len: usize) -> u32
+        where
+            T::Item: Send,
+        {
+            let locations = Mutex::new(HashMap::<BranchId, Vec<usize>>::new());
+            iter.for_each_with_location(|id, i, _| {
+                locations.lock().unwrap().entry(id).or_default().push(i);
+            });
+
+            let mut locations = locations.into_inner().unwrap();
+            let mut count = 0;
+            for indices in locations.values_mut() {
+                indices.sort_unstable();
+                assert!(indices.iter().copied().eq(0..indices.len()));
+                count += indices.len();
+            }
+            assert_eq!(count, len);
+            locations.keys().map(|id| id.depth()).max().unwrap()
+        }
+
+        check(AllNumbers::new().par_split(), AllNumbers::COUNT);
+
+        // The split tree would be 1000 levels deep, but the IDs stop it at the maximum depth
+        let deep = Linear(0..1000)
+            .par_split()
+            .with_split_token_bucket(0.0, usize::MAX);
+        assert_eq!(check(deep, 1000), BranchId::MAX_DEPTH);
+    }
+
+    #[test]
+    fn test_collect_branches() {
+        let branches = AllNumbers::new().par_split().collect_branches();
+        assert!(branches.iter().all(|items| !items.is_empty()));
+
+        let mut actual: Vec<_> = branches.into_iter().flatten().collect();
+        actual.sort_unstable();
+        let expected: Vec<_> = (1..=AllNumbers::COUNT as u32).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reduce_deterministic() {
+        use rayon::ThreadPoolBuilder;
+
+        /// Wraps each number in a vector, so we can concatenate them.
+        struct Singletons(AllNumbers);
+
+        impl Iterator for Singletons {
+            type Item = Vec<u32>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next().map(|n| vec![n])
+            }
+        }
+
+        impl Spliterator for Singletons {
+            fn split(&mut self) -> Option<Self> {
+                self.0.split().map(Self)
+            }
+        }
+
+        let concat = |mut a: Vec<u32>, mut b: Vec<u32>| {
+            a.append(&mut b);
+            a
+        };
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let run = || {
+            pool.install(|| {
+                Singletons(AllNumbers::new())
+                    .par_split()
+                    .reduce_deterministic(Vec::new, concat)
+            })
+        };
+
+        let first = run();
+        assert_eq!(first.len(), AllNumbers::COUNT);
+        for _ in 0..20 {
+            assert_eq!(run(), first);
+        }
+    }
+
+    #[test]
+    fn test_with_worker_init() {
+        use rayon::ThreadPoolBuilder;
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let inits = AtomicUsize::new(0);
+        let branches = pool.install(|| {
+            count_branches(AllNumbers::new().par_split().with_worker_init(|| {
+                inits.fetch_add(1, Ordering::Relaxed);
+            }))
+        });
+
+        let inits = inits.into_inner();
+        assert!(inits >= 1);
+        assert!(inits <= 4);
+        assert!(inits <= branches);
+
+        // Each run of a clone is a separate run
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let inits = AtomicUsize::new(0);
+        let iter = AllNumbers::new().par_split().with_worker_init(|| {
+            inits.fetch_add(1, Ordering::Relaxed);
+        });
+        pool.install(|| {
+            assert_eq!(iter.clone().count(), AllNumbers::COUNT);
+            assert_eq!(iter.count(), AllNumbers::COUNT);
+        });
+        assert_eq!(inits.into_inner(), 2);
+    }
+
+    #[test]
+    fn test_measure() {
+        let stats = AllNumbers::new().par_split().measure();
+        assert_eq!(stats.items, AllNumbers::COUNT);
+        assert!(stats.elapsed > Duration::ZERO);
+        assert!(stats.threads >= 1);
+        assert!(stats.items_per_sec() > 0.0);
+
+        let stats = VecSpliter::from(Vec::<u32>::new()).par_split().measure();
+        assert_eq!(stats.items, 0);
+        assert_eq!(stats.items_per_sec(), 0.0);
+
+        // Too fast to measure
+        let stats = RunStats {
+            items: 10,
+            elapsed: Duration::ZERO,
+            ..stats
+        };
+        assert_eq!(stats.items_per_sec(), 0.0);
+    }
+
+    #[test]
+    fn test_should_split() {
+        let iter = TestRange::new(0..100_000);
+        let veto = iter.veto.clone();
+        veto.store(true, Ordering::Relaxed);
+
+        // Lift the veto partway through
+        let branches = count_branches(iter.par_split().inspect(|&n| {
+            if n == 1000 {
+                veto.store(false, Ordering::Relaxed);
+            }
+        }));
+        assert!(!veto.load(Ordering::Relaxed));
+        assert!(branches > 1);
+    }
+
+    #[test]
+    fn test_collect_into_slice() {
+        let mut out = vec![0; 100_000];
+        let items: Vec<u32> = (0..100_000).collect();
+        VecSpliter::from(items)
+            .par_split()
+            .collect_into_slice(&mut out);
+        assert!(out.into_iter().eq(0..100_000));
+
+        let mut out = vec![String::new(); 1000];
+        let strings: Vec<_> = (0..1000).map(|i| i.to_string()).collect();
+        VecSpliter::from(strings.clone())
+            .par_split()
+            .collect_into_slice(&mut out);
+        assert_eq!(out, strings);
+    }
+
+    #[test]
+    #[should_panic(expected = "length mismatch")]
+    fn test_collect_into_slice_mismatch() {
+        let mut out = vec![0; 10];
+        VecSpliter::from(vec![0; 20])
+            .par_split()
+            .collect_into_slice(&mut out);
+    }
+
+    #[test]
+    fn test_tree_search() {
+        let children = |&n: &u32| {
+            if n < 1 << 15 {
+                vec![2 * n, 2 * n + 1]
+            } else {
+                vec![]
+            }
+        };
+
+        let mut actual: Vec<_> = tree_search(1, children).collect();
+        actual.sort_unstable();
+        let expected: Vec<_> = (1..=AllNumbers::COUNT as u32).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_with_max_threads() {
+        use rayon::ThreadPoolBuilder;
+        use std::thread::ThreadId;
+
+        let pool = ThreadPoolBuilder::new().num_threads(8).build().unwrap();
+        for m in [1, 2, 3, 4] {
+            let threads = Mutex::new(HashSet::<ThreadId>::new());
+            let count = pool.install(|| {
+                AllNumbers::new()
+                    .par_split()
+                    .with_max_threads(m)
+                    .inspect(|_| {
+                        threads.lock().unwrap().insert(thread::current().id());
+                    })
+                    .count()
+            });
+            assert_eq!(count, AllNumbers::COUNT);
+            assert!(threads.into_inner().unwrap().len() <= m);
+        }
+    }
+
+    #[test]
+    fn test_top_k_by_key() {
+        // Rank by the bit-reversal, so the best items aren't just the last ones
+        let key = |n: &u32| n.reverse_bits();
+
+        let mut expected: Vec<_> = AllNumbers::new().collect();
+        expected.sort_unstable_by_key(|n| Reverse(key(n)));
+        expected.truncate(10);
+
+        let actual = AllNumbers::new().par_split().top_k_by_key(10, key);
+        assert_eq!(actual, expected);
+
+        assert!(AllNumbers::new()
+            .par_split()
+            .top_k_by_key(0, key)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_for_each_batch() {
+        let items = AtomicUsize::new(0);
+        let sum = AtomicU64::new(0);
+        AllNumbers::new().par_split().for_each_batch(100, |batch| {
+            assert!(!batch.is_empty());
+            assert!(batch.len() <= 100);
+            items.fetch_add(batch.len(), Ordering::Relaxed);
+            let batch_sum: u64 = batch.iter().copied().map(u64::from).sum();
+            sum.fetch_add(batch_sum, Ordering::Relaxed);
+        });
+
+        let count = AllNumbers::COUNT as u64;
+        assert_eq!(items.into_inner(), AllNumbers::COUNT);
+        assert_eq!(sum.into_inner(), count * (count + 1) / 2);
+    }
+
+    #[test]
+    fn test_split_eagerly() {
+        /// Panics if it's split after yielding any items.
+        struct Eager(VecSpliter<u32>, bool);
+
+        impl Iterator for Eager {
+            type Item = u32;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.1 = true;
+                self.0.next()
+            }
+        }
+
+        impl Spliterator for Eager {
+            fn split(&mut self) -> Option<Self> {
+                assert!(!self.1, "split after consuming");
+                self.0.split().map(|split| Self(split, false))
+            }
+        }
+
+        let items: Vec<_> = (0..100_000).collect();
+        let eager = Eager(VecSpliter::from(items.clone()), false);
+        let mut actual: Vec<_> = eager.par_split().split_eagerly().collect();
+        actual.sort_unstable();
+        assert_eq!(actual, items);
+
+        let count = AllNumbers::new().par_split().split_eagerly().count();
+        assert_eq!(count, AllNumbers::COUNT);
+
+        // The root can't split until it has consumed something, so it never does
+        let branches = count_branches(AllNumbers::new().par_split().split_eagerly());
+        assert_eq!(branches, 1);
+    }
+
+    #[test]
+    fn test_fold_ordered_per_branch() {
+        let items: Vec<u32> = (0..100_000).collect();
+        let branches = VecSpliter::from(items.clone())
+            .par_split()
+            .fold_ordered_per_branch(Vec::new, |mut acc, n| {
+                acc.push(n);
+                acc
+            });
+
+        // VecSpliter keeps the earlier half, so the branches are in sequential order
+        let actual: Vec<_> = branches.into_iter().flatten().collect();
+        assert_eq!(actual, items);
+    }
+
+    #[test]
+    fn test_collect_partition() {
+        let items: Vec<Result<u32, String>> = (0..10_000)
+            .map(|n| {
+                if n % 7 == 0 {
+                    Err(n.to_string())
+                } else {
+                    Ok(n)
+                }
+            })
+            .collect();
+
+        let (mut oks, mut errs) = VecSpliter::from(items).par_split().collect_partition();
+        oks.sort_unstable();
+        errs.sort_unstable_by_key(|e| e.parse::<u32>().unwrap());
+
+        let expected_oks: Vec<_> = (0..10_000).filter(|n| n % 7 != 0).collect();
+        let expected_errs: Vec<_> = (0..10_000)
+            .filter(|n| n % 7 == 0)
+            .map(|n: u32| n.to_string())
+            .collect();
+        assert_eq!(oks, expected_oks);
+        assert_eq!(errs, expected_errs);
+    }
+
+    #[test]
+    fn test_with_split_retry() {
+        // AllNumbers can't split until it consumes the root
+        let branches =
+            |attempts| count_branches(AllNumbers::new().par_split().with_split_retry(attempts));
+        assert_eq!(branches(0), 1);
+        assert!(branches(1) > 1);
+
+        let count = AllNumbers::new().par_split().with_split_retry(1).count();
+        assert_eq!(count, AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_seq_iter() {
+        let par = AllNumbers::new().par_split().count();
+        let seq = AllNumbers::new().par_split().seq_iter().count();
+        assert_eq!(seq, par);
+
+        let items: Vec<_> = RangeInclusiveSpliter::from(0..=9u32)
+            .par_split()
+            .enumerate_branch()
+            .seq_iter()
+            .collect();
+        assert!(items.into_iter().eq((0..10).map(|n| (n as usize, n))));
+    }
+
+    #[test]
+    fn test_with_reduce_threshold() {
+        /// A histogram of the low bits, and the depth of the merge tree that built it.
+        type Histogram = ([u32; 64], u32);
+
+        let leaves = AtomicUsize::new(0);
+        let identity = || {
+            leaves.fetch_add(1, Ordering::Relaxed);
+            ([0; 64], 0)
+        };
+        let fold = |(mut counts, depth): Histogram, n: u32| {
+            counts[n as usize % 64] += 1;
+            (counts, depth)
+        };
+        let merge = |(mut a, m): Histogram, (b, n): Histogram| {
+            for (x, y) in a.iter_mut().zip(b) {
+                *x += y;
+            }
+            (a, m.max(n) + 1)
+        };
+
+        let (counts, depth) = AllNumbers::new()
+            .par_split()
+            .with_reduce_threshold(64)
+            .fold_reduce(identity, fold, merge);
+        assert_eq!(counts.iter().sum::<u32>(), AllNumbers::COUNT as u32);
+        let leaves = leaves.into_inner();
+        assert!(depth <= leaves.next_power_of_two().ilog2());
+
+        let (counts, _) = AllNumbers::new()
+            .par_split()
+            .fold_reduce(|| ([0; 64], 0), fold, merge);
+        assert_eq!(counts.iter().sum::<u32>(), AllNumbers::COUNT as u32);
+    }
+
+    #[cfg(feature = "scoped")]
+    #[test]
+    fn test_par_split_scoped_threads() {
+        for threads in [1, 2, 4] {
+            let count = par_split_scoped_threads(
+                AllNumbers::new(),
+                threads,
+                || 0,
+                |n, _| n + 1,
+                |a, b| a + b,
+            );
+            assert_eq!(count, AllNumbers::COUNT);
+        }
+    }
+
+    #[test]
+    fn test_with_duplicate_check() {
+        let count = AllNumbers::new().par_split().with_duplicate_check().count();
+        assert_eq!(count, AllNumbers::COUNT);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "duplicate item")]
+    fn test_with_duplicate_check_panics() {
+        /// Hands the whole range to both halves of its first split.
+        struct Buggy(std::ops::Range<u32>, bool);
+
+        impl Iterator for Buggy {
+            type Item = u32;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next()
+            }
+        }
+
+        impl Spliterator for Buggy {
+            fn split(&mut self) -> Option<Self> {
+                if self.1 {
+                    None
+                } else {
+                    self.1 = true;
+                    Some(Self(self.0.clone(), true))
+                }
+            }
+        }
+
+        Buggy(0..1000, false)
+            .par_split()
+            .with_duplicate_check()
+            .for_each(|_| {});
+    }
+
+    #[test]
+    fn test_fold_into_vecs_with_capacity() {
+        let vecs: Vec<_> = AllNumbers::new()
+            .par_split()
+            .fold_into_vecs_with_capacity(1000)
+            .collect();
+        assert!(vecs.iter().all(|vec| vec.capacity() >= 1000));
+
+        let mut actual: Vec<_> = vecs.into_iter().flatten().collect();
+        actual.sort_unstable();
+        let expected: Vec<_> = (1..=AllNumbers::COUNT as u32).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_with_split_ratio() {
+        let items: Vec<_> = (0..1000).collect();
+        let split = |r| {
+            let mut iter = VecSpliter::from(items.clone())
+                .par_split()
+                .with_split_ratio(r);
+            iter.splits = 10;
+            let split = iter.split().unwrap();
+            (iter.splits, split.splits)
+        };
+
+        assert_eq!(split(0.5), (5, 5));
+        assert_eq!(split(0.9), (9, 1));
+        assert_eq!(split(2.0), (10, 0));
+
+        let count = AllNumbers::new().par_split().with_split_ratio(0.9).count();
+        assert_eq!(count, AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_drive_to_sender() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        assert!(AllNumbers::new().par_split().drive_to_sender(tx).is_ok());
+        let mut actual: Vec<_> = rx.into_iter().collect();
+        actual.sort_unstable();
+        assert_eq!(actual, (1..=AllNumbers::COUNT as u32).collect::<Vec<_>>());
+
+        // This would run practically forever if hanging up didn't stop it
+        let (tx, rx) = std::sync::mpsc::channel();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let consumed = Arc::clone(&counter);
+        let consumer = thread::spawn(move || {
+            let received = rx.iter().take(100).count();
+            drop(rx);
+            (received, consumed.load(Ordering::SeqCst))
+        });
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let result = pool.install(|| {
+            RangeInclusiveSpliter::new(0, u64::MAX)
+                .par_split()
+                .with_item_counter(Arc::clone(&counter))
+                .drive_to_sender(tx)
+        });
+        assert!(result.is_err());
+
+        // After the hang-up, each thread gets at most one more item before its send fails
+        let (received, at_hangup) = consumer.join().unwrap();
+        assert_eq!(received, 100);
+        let after = counter.load(Ordering::SeqCst) - at_hangup;
+        assert!(after <= 4, "{after}");
+    }
+
+    #[test]
+    fn test_with_branch_bloom() {
+        let items: Vec<_> = (0..10_000).map(|n| n % 1000).collect();
+        let actual: HashSet<_> = VecSpliter::from(items)
+            .par_split()
+            .with_branch_bloom(1 << 20)
+            .collect();
+        assert_eq!(actual, (0..1000).collect());
+    }
+
+    #[test]
+    fn test_reduce_tree() {
+        enum SumTree {
+            Leaf(u32),
+            Node(u64, Box<SumTree>, Box<SumTree>),
+        }
+
+        impl SumTree {
+            fn sum(&self) -> u64 {
+                match self {
+                    Self::Leaf(n) => *n as u64,
+                    Self::Node(sum, _, _) => *sum,
+                }
+            }
+
+            fn leaves(&self) -> usize {
+                match self {
+                    Self::Leaf(_) => 1,
+                    Self::Node(_, left, right) => left.leaves() + right.leaves(),
+                }
+            }
+        }
+
+        let tree = AllNumbers::new()
+            .par_split()
+            .reduce_tree(SumTree::Leaf, |left, right| {
+                let sum = left.sum() + right.sum();
+                SumTree::Node(sum, Box::new(left), Box::new(right))
+            })
+            .unwrap();
+
+        let count = AllNumbers::COUNT as u64;
+        assert_eq!(tree.sum(), count * (count + 1) / 2);
+        assert_eq!(tree.leaves(), AllNumbers::COUNT);
+
+        let empty = VecSpliter::from(Vec::<u32>::new())
+            .par_split()
+            .reduce_tree(|n| n, |a, b| a + b);
+        assert_eq!(empty, None);
+    }
+
+    #[test]
+    fn test_with_rate_limit() {
+        let start = Instant::now();
+        let count = RangeInclusiveSpliter::from(0..=299)
+            .par_split()
+            .with_rate_limit(1000)
+            .count();
+        let elapsed = start.elapsed();
+
+        // The last item can't go until 299 slots after the first
+        assert_eq!(count, 300);
+        assert!(elapsed >= Duration::from_millis(299), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(3), "{elapsed:?}");
+    }
+
+    #[test]
+    fn test_route_into() {
+        let mut left = vec![0];
+        let mut right = Vec::new();
+        AllNumbers::new()
+            .par_split()
+            .route_into(&mut left, &mut right, |n| match n % 3 {
+                0 => Route::Left,
+                1 => Route::Right,
+                _ => Route::Drop,
+            });
+
+        assert_eq!(left[0], 0);
+        assert_eq!(left.len(), 1 + AllNumbers::COUNT / 3);
+        assert_eq!(right.len(), AllNumbers::COUNT / 3);
+        assert!(left[1..].iter().all(|n| n % 3 == 0));
+        assert!(right.iter().all(|n| n % 3 == 1));
+    }
+
+    #[test]
+    fn test_max_depth_by() {
+        let depth = AllNumbers::new().par_split().max_depth_by(|n| n.ilog2());
+        assert_eq!(depth, 15);
+
+        let empty = VecSpliter::from(Vec::<u32>::new()).par_split();
+        assert_eq!(empty.max_depth_by(|_| 1), 0);
+    }
+
+    #[test]
+    fn test_split_on_demand() {
+        use rayon::ThreadPoolBuilder;
+
+        // With one thread, the other half of the join stays queued, so nothing is stolen
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let (eager, lazy) = pool.install(|| {
+            let eager = rayon::join(|| count_branches(AllNumbers::new().par_split()), || ()).0;
+            let lazy = rayon::join(
+                || count_branches(AllNumbers::new().par_split().split_on_demand()),
+                || (),
+            )
+            .0;
+            (eager, lazy)
+        });
+        assert!(eager > 1);
+        assert_eq!(lazy, 1);
+
+        let count = AllNumbers::new().par_split().split_on_demand().count();
+        assert_eq!(count, AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_run_length_encode() {
+        let items: Vec<_> = (0..100_000u32).map(|n| n / 7 + n / 1000).collect();
+
+        let mut expected: Vec<(u32, usize)> = Vec::new();
+        for &n in &items {
+            match expected.last_mut() {
+                Some((last, count)) if *last == n => *count += 1,
+                _ => expected.push((n, 1)),
+            }
+        }
+
+        let actual = VecSpliter::from(items).par_split().run_length_encode();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_single_thread_mode() {
+        use rayon::ThreadPoolBuilder;
+
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let iter = TestRange::new(0..1000).with_divisor(0);
+        let sum: u32 = pool.install(|| iter.par_split().single_thread_mode().sum());
+        assert_eq!(sum, (0..1000).sum::<u32>());
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let branches =
+            pool.install(|| count_branches(AllNumbers::new().par_split().single_thread_mode()));
+        assert!(branches > 1);
+    }
+
+    #[test]
+    fn test_with_metrics_sink() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let count = AllNumbers::new()
+            .par_split()
+            .with_metrics_sink(Duration::from_millis(1), move |metrics| {
+                sink.lock().unwrap().push(metrics);
+            })
+            .inspect(|_| thread::sleep(Duration::from_micros(10)))
+            .count();
+        assert_eq!(count, AllNumbers::COUNT);
+
+        let reports = reports.lock().unwrap();
+        assert!(reports.len() >= 2, "{reports:?}");
+
+        let last = reports.last().unwrap();
+        assert_eq!(last.branches, 0);
+        assert_eq!(last.items, AllNumbers::COUNT as u64);
+        assert!(reports.windows(2).all(|w| w[0].items <= w[1].items));
+        assert!(reports.windows(2).all(|w| w[0].splits <= w[1].splits));
+        drop(reports);
+
+        // Nothing is reported until the run starts
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let iter = AllNumbers::new().par_split().with_metrics_sink(
+            Duration::from_millis(1),
+            move |metrics| {
+                sink.lock().unwrap().push(metrics);
+            },
+        );
+        thread::sleep(Duration::from_millis(20));
+        assert!(reports.lock().unwrap().is_empty());
+        drop(iter);
+        assert!(reports.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_split_until_balanced() {
+        use rayon::ThreadPoolBuilder;
+
+        // Only splits off an eighth of its items at a time
+        let lopsided = || TestRange::new(0..100_000).with_divisor(8);
+
+        fn largest<T: Spliterator<Item = u32> + Send>(iter: ParSpliter<T>) -> usize {
+            let branches = iter.deterministic().collect_branches();
+            assert_eq!(branches.iter().map(Vec::len).sum::<usize>(), 100_000);
+            branches.iter().map(Vec::len).max().unwrap()
+        }
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let (skewed, balanced, stacked) = pool.install(|| {
+            let skewed = largest(lopsided().par_split());
+            let balanced = largest(lopsided().par_split().split_until_balanced(0.5));
+
+            // The size hints have to make it through the adapters
+            let stacked = lopsided()
+                .par_split()
+                .with_item_counter(Arc::new(AtomicUsize::new(0)))
+                .with_cancel(Arc::new(AtomicBool::new(false)))
+                .split_until_balanced(0.5);
+            (skewed, balanced, largest(stacked))
+        });
+
+        // With the usual budget, the root only splits a few times, keeping most of the items
+        assert!(skewed > 50_000, "{skewed}");
+        assert!(balanced <= 12_500, "{balanced}");
+        assert!(stacked <= 12_500, "{stacked}");
+    }
+
+    #[test]
+    fn test_concat_strings() {
+        let strings: Vec<_> = (0..10_000).map(|n| n.to_string()).collect();
+        let expected = strings.concat();
+
+        let concat = || {
+            VecSpliter::from(strings.clone())
+                .par_split()
+                .deterministic()
+                .concat_strings()
+        };
+        assert_eq!(concat(), expected);
+        assert_eq!(concat(), concat());
+
+        let strs: Vec<_> = strings.iter().map(String::as_str).collect();
+        assert_eq!(
+            VecSpliter::from(strs).par_split().concat_strings(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_concat_vecs() {
+        let vecs: Vec<_> = (0..1000).map(|n| vec![n; n % 7]).collect();
+        let expected = vecs.concat();
+
+        let actual = VecSpliter::from(vecs).par_split().concat_vecs();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_peek_frontier() {
+        let pause = Arc::new(AtomicBool::new(false));
+        let seen = Mutex::new(Vec::new());
+        let f = |n| {
+            let mut seen = seen.lock().unwrap();
+            seen.push(n);
+            if seen.len() == 1000 {
+                pause.store(true, Ordering::Relaxed);
+            }
+        };
+
+        let frontier = AllNumbers::new()
+            .par_split()
+            .peek_frontier(pause.clone(), f);
+        assert!(!frontier.is_empty());
+        let remaining: usize = frontier.iter().map(|iter| iter.clone().count()).sum();
+        assert!(remaining > 0);
+        assert_eq!(seen.lock().unwrap().len() + remaining, AllNumbers::COUNT);
+
+        pause.store(false, Ordering::Relaxed);
+        let rest = par_split_fair(frontier).peek_frontier(pause.clone(), f);
+        assert!(rest.is_empty());
+
+        let mut seen = seen.into_inner().unwrap();
+        seen.sort_unstable();
+        assert_eq!(seen, (1..=AllNumbers::COUNT as u32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_fold_chunks_with() {
+        let mut expected = [0usize; 16];
+        for n in AllNumbers::new() {
+            expected[n as usize % 16] += 1;
+        }
+
+        let histogram = AllNumbers::new().par_split().fold_chunks_with(
+            64,
+            || [0usize; 16],
+            |hist, chunk| {
+                assert!(chunk.len() <= 64);
+                for &n in chunk {
+                    hist[n as usize % 16] += 1;
+                }
+            },
+            |mut left, right| {
+                for (l, r) in left.iter_mut().zip(right) {
+                    *l += r;
+                }
+                left
+            },
+        );
+        assert_eq!(histogram, expected);
+    }
+
+    #[test]
+    fn test_into_spliterator() {
+        assert_eq!(vec![1, 2, 3].par_split().sum::<i32>(), 6);
+
+        let items: Vec<_> = (0..10_000).collect();
+        let total: u64 = items.clone().par_split().sum();
+        assert_eq!(total, items.iter().sum::<u64>());
+    }
+
+    #[test]
+    fn test_with_branch_counter() {
+        use rayon::ThreadPoolBuilder;
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let folds = pool.install(|| {
+            count_branches(
+                AllNumbers::new()
+                    .par_split()
+                    .with_branch_counter(counter.clone()),
+            )
+        });
+
+        // Every split completes the partial fold before it, and adds two more
+        let splits = (folds - 1) / 2;
+        assert!(splits > 0);
+        assert_eq!(counter.load(Ordering::Relaxed), splits + 1);
+    }
+
+    #[test]
+    fn test_with_overflow_checked_splits() {
+        let mut iter = AllNumbers::new().par_split();
+        iter.splits = usize::MAX;
+
+        let iter = iter.with_overflow_checked_splits();
+        assert_eq!(iter.splits, ParSpliter::<AllNumbers>::MAX_SPLITS);
+        assert_eq!(iter.count(), AllNumbers::COUNT);
+    }
+
+    #[test]
+    fn test_capture_schedule() {
+        use rayon::ThreadPoolBuilder;
+
+        let branches = |iter: ParSpliter<Scheduled<AllNumbers>>| {
+            iter.fold(|| 0, |n, _| n + 1)
+                .filter(|&n| n > 0)
+                .collect::<Vec<usize>>()
+        };
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let (captured, schedule) =
+            pool.install(|| AllNumbers::new().par_split().capture_schedule(branches));
+        assert!(schedule.split_count() > 0);
+        assert_eq!(captured.iter().sum::<usize>(), AllNumbers::COUNT);
+
+        // Replay in a different pool, and capture the replay too
+        let pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+        let (replayed, again) = pool.install(|| {
+            AllNumbers::new()
+                .par_split()
+                .replay(&schedule)
+                .capture_schedule(|iter| iter.fold(|| 0, |n, _| n + 1).filter(|&n| n > 0).count())
+        });
+        assert_eq!(again, schedule);
+        assert_eq!(replayed, captured.len());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_with_tracing_name() {
+        use rayon::ThreadPoolBuilder;
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// The name and depth fields of a span.
+        #[derive(Default)]
+        struct Fields(Option<String>, Option<u64>);
+
+        impl Visit for Fields {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                if field.name() == "name" {
+                    self.0 = Some(value.to_owned());
+                }
+            }
+
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                if field.name() == "depth" {
+                    self.1 = Some(value);
+                }
+            }
+
+            fn record_debug(&mut self, _: &Field, _: &dyn Debug) {}
+        }
+
+        /// Records the fields of every new span.
+        #[derive(Clone, Default)]
+        struct Spans(Arc<Mutex<Vec<Fields>>>);
+
+        impl Subscriber for Spans {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut fields = Fields::default();
+                span.record(&mut fields);
+                let mut spans = self.0.lock().unwrap();
+                spans.push(fields);
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &Event<'_>) {}
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        // The subscriber is only installed on the one worker thread
+        let spans = Spans::default();
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let count = pool.install(|| {
+            tracing::subscriber::with_default(spans.clone(), || {
+                AllNumbers::new()
+                    .par_split()
+                    .with_tracing_name("numbers")
+                    .count()
+            })
+        });
+        assert_eq!(count, AllNumbers::COUNT);
+
+        let spans = spans.0.lock().unwrap();
+        assert!(spans.len() > 1, "{}", spans.len());
+        assert!(spans
+            .iter()
+            .all(|span| span.0.as_deref() == Some("numbers")));
+        assert_eq!(spans.iter().filter(|span| span.1 == Some(0)).count(), 1);
+        assert!(spans.iter().all(|span| span.1.is_some()));
+
+        // Without a name, there are no spans
+        let spans = Spans::default();
+        pool.install(|| {
+            tracing::subscriber::with_default(spans.clone(), || {
+                AllNumbers::new().par_split().count()
+            })
+        });
+        assert!(spans.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ordered_max_recursion() {
+        use rayon::ThreadPoolBuilder;
+
+        let pool = ThreadPoolBuilder::new().num_threads(8).build().unwrap();
+        let items: Vec<u32> = (0..100_000).collect();
+
+        // The work queue would combine these out of order, so the branches stop splitting instead
+        let branches = pool.install(|| {
+            VecSpliter::from(items.clone())
+                .par_split()
+                .with_split_token_bucket(0.0, usize::MAX)
+                .with_max_recursion(2)
+                .fold_ordered_per_branch(Vec::new, |mut acc, n| {
+                    acc.push(n);
+                    acc
+                })
+        });
+        let actual: Vec<_> = branches.into_iter().flatten().collect();
+        assert_eq!(actual, items);
 
-        assert_eq!(AllNumbers::new().count(), (1 << 16) - 1);
-        assert_eq!(AllNumbers::new().par_split().count(), (1 << 16) - 1);
+        let strings: Vec<_> = (0..10_000).map(|n| n.to_string()).collect();
+        let concat = pool.install(|| {
+            VecSpliter::from(strings.clone())
+                .par_split()
+                .with_split_token_bucket(0.0, usize::MAX)
+                .with_max_recursion(1)
+                .concat_strings()
+        });
+        assert_eq!(concat, strings.concat());
     }
 }
@@ -0,0 +1,79 @@
+//! Limiting the total number of items.
+
+use crate::Spliterator;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A [Spliterator] that yields at most a fixed number of items across all of its splits.
+///
+/// Created by [`ParSpliter::limit_split()`](crate::ParSpliter::limit_split).
+#[derive(Debug)]
+pub struct Limit<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The number of items left to yield, shared by every split.
+    remaining: Arc<AtomicUsize>,
+}
+
+impl<T> Limit<T> {
+    pub(crate) fn new(iter: T, n: usize) -> Self {
+        Self {
+            iter,
+            remaining: Arc::new(AtomicUsize::new(n)),
+        }
+    }
+
+    /// Check whether the limit has been reached.
+    fn exhausted(&self) -> bool {
+        self.remaining.load(Ordering::Relaxed) == 0
+    }
+}
+
+impl<T: Iterator> Iterator for Limit<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted() {
+            return None;
+        }
+
+        // Claim a slot only once we actually have an item, so that exhausted
+        // branches don't use up the budget of the others
+        let item = self.iter.next()?;
+        self.remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .ok()
+            .map(|_| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Other branches may use up the shared budget first
+        let remaining = self.remaining.load(Ordering::Relaxed);
+        let (_, hi) = self.iter.size_hint();
+        (0, Some(hi.map_or(remaining, |hi| hi.min(remaining))))
+    }
+}
+
+impl<T: Spliterator> Spliterator for Limit<T> {
+    fn split(&mut self) -> Option<Self> {
+        if self.exhausted() {
+            return None;
+        }
+
+        self.iter.split().map(|iter| Self {
+            iter,
+            remaining: self.remaining.clone(),
+        })
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        let remaining = self.remaining.load(Ordering::Relaxed);
+        let max = self.iter.max_items_per_branch().unwrap_or(remaining);
+        Some(max.min(remaining))
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
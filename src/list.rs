@@ -0,0 +1,93 @@
+//! Splitting linked lists.
+
+use crate::{ParSpliter, Spliterator};
+
+use std::collections::LinkedList;
+
+/// A [Spliterator] over the items of a [LinkedList], by value.
+///
+/// Splits hand off the later half of the list with [`LinkedList::split_off()`].  Finding the
+/// midpoint takes O(n) time to walk the list, but detaching it is O(1), and no items are moved.
+#[derive(Clone, Debug)]
+pub struct LinkedListSpliter<T> {
+    /// The items we have yet to yield.
+    list: LinkedList<T>,
+}
+
+impl<T> LinkedListSpliter<T> {
+    /// Create a spliterator that yields the items of `list`, in order.
+    pub fn new(list: LinkedList<T>) -> Self {
+        Self { list }
+    }
+}
+
+impl<T> From<LinkedList<T>> for LinkedListSpliter<T> {
+    fn from(list: LinkedList<T>) -> Self {
+        Self::new(list)
+    }
+}
+
+impl<T> Iterator for LinkedListSpliter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.list.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for LinkedListSpliter<T> {}
+
+impl<T> Spliterator for LinkedListSpliter<T> {
+    fn split(&mut self) -> Option<Self> {
+        let len = self.list.len();
+        if len >= 2 {
+            let later = self.list.split_off(len / 2);
+            Some(Self { list: later })
+        } else {
+            None
+        }
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        Some(self.list.len())
+    }
+}
+
+/// Extension trait for iterating over a [LinkedList] in parallel.
+pub trait ParallelSplitList<T> {
+    /// Iterate over the items of this list in parallel, by value.
+    fn par_split_list(self) -> ParSpliter<LinkedListSpliter<T>>;
+}
+
+impl<T> ParallelSplitList<T> for LinkedList<T> {
+    fn par_split_list(self) -> ParSpliter<LinkedListSpliter<T>> {
+        ParSpliter::new(LinkedListSpliter::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rayon::iter::ParallelIterator;
+
+    #[test]
+    fn test_linked_list_spliter() {
+        let mut iter = LinkedListSpliter::new((0..10).collect());
+        assert_eq!(iter.next(), Some(0));
+
+        let split = iter.split().unwrap();
+        assert_eq!(iter.collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(split.collect::<Vec<_>>(), [5, 6, 7, 8, 9]);
+
+        let list: LinkedList<u32> = (0..10_000).collect();
+        let mut items: Vec<_> = list.par_split_list().collect();
+        items.sort_unstable();
+        assert!(items.into_iter().eq(0..10_000));
+    }
+}
@@ -0,0 +1,213 @@
+//! Periodic metrics while a run is in progress.
+
+use crate::Spliterator;
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// The callback that receives the metrics.
+type Report = Box<dyn Fn(LiveMetrics) + Send + Sync>;
+
+/// A snapshot of a run in progress, from
+/// [`ParSpliter::with_metrics_sink()`](crate::ParSpliter::with_metrics_sink).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LiveMetrics {
+    /// The number of branches that haven't finished yet.
+    pub branches: usize,
+    /// The number of items produced so far.
+    pub items: u64,
+    /// The number of splits so far.
+    pub splits: u64,
+}
+
+/// The counters shared by every branch and the monitor thread.
+struct Metrics {
+    /// The number of live branches.
+    branches: AtomicUsize,
+    /// The number of items produced.
+    items: AtomicU64,
+    /// The number of splits.
+    splits: AtomicU64,
+    /// Whether every branch has finished.
+    done: Mutex<bool>,
+    /// Wakes up the monitor when we're done.
+    wake: Condvar,
+    /// The reporting interval and callback, until the monitor thread starts.
+    pending: Mutex<Option<(Duration, Report)>>,
+    /// The monitor thread.
+    monitor: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Metrics {
+    /// Start the monitor thread, unless it's already running.
+    fn start(self: &Arc<Self>) {
+        let Some((interval, f)) = self.pending.lock().unwrap().take() else {
+            return;
+        };
+
+        let shared = self.clone();
+        let monitor = thread::spawn(move || {
+            loop {
+                let done = shared.done.lock().unwrap();
+                let (done, _) = shared
+                    .wake
+                    .wait_timeout_while(done, interval, |done| !*done)
+                    .unwrap();
+                if *done {
+                    break;
+                }
+
+                // Don't hold the lock during the callback, or it would hold up the end of the run
+                drop(done);
+                f(shared.snapshot());
+            }
+
+            // One last report with the final counts
+            f(shared.snapshot());
+        });
+        *self.monitor.lock().unwrap() = Some(monitor);
+    }
+
+    /// Take a snapshot of the counters.
+    fn snapshot(&self) -> LiveMetrics {
+        LiveMetrics {
+            branches: self.branches.load(Ordering::Relaxed),
+            items: self.items.load(Ordering::Relaxed),
+            splits: self.splits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Debug for Metrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Metrics")
+            .field("branches", &self.branches)
+            .field("items", &self.items)
+            .field("splits", &self.splits)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [Spliterator] that reports live metrics to a callback from a monitor thread.
+///
+/// Created by [`ParSpliter::with_metrics_sink()`](crate::ParSpliter::with_metrics_sink).
+#[derive(Debug)]
+pub struct MetricsSink<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The shared counters.
+    metrics: Arc<Metrics>,
+    /// The number of items produced since we last updated the counters.
+    pending: u64,
+    /// Whether this branch has made sure the monitor is running.
+    started: bool,
+}
+
+impl<T> MetricsSink<T> {
+    /// The number of items to produce between updates of the shared counters.
+    const BATCH: u64 = 256;
+
+    pub(crate) fn new<F>(iter: T, interval: Duration, f: F) -> Self
+    where
+        F: Fn(LiveMetrics) + Send + Sync + 'static,
+    {
+        let metrics = Arc::new(Metrics {
+            branches: AtomicUsize::new(1),
+            items: AtomicU64::new(0),
+            splits: AtomicU64::new(0),
+            done: Mutex::new(false),
+            wake: Condvar::new(),
+            pending: Mutex::new(Some((interval, Box::new(f)))),
+            monitor: Mutex::new(None),
+        });
+
+        Self {
+            iter,
+            metrics,
+            pending: 0,
+            started: false,
+        }
+    }
+
+    /// Start the monitor once the run does, rather than when the adapter is created.
+    fn start(&mut self) {
+        if !self.started {
+            self.started = true;
+            self.metrics.start();
+        }
+    }
+
+    /// Add the pending items to the shared counter.
+    fn flush(&mut self) {
+        if self.pending > 0 {
+            self.metrics
+                .items
+                .fetch_add(self.pending, Ordering::Relaxed);
+            self.pending = 0;
+        }
+    }
+}
+
+impl<T: Iterator> Iterator for MetricsSink<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.start();
+
+        let item = self.iter.next();
+        if item.is_some() {
+            self.pending += 1;
+            if self.pending == Self::BATCH {
+                self.flush();
+            }
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for MetricsSink<T> {
+    fn split(&mut self) -> Option<Self> {
+        self.start();
+
+        let iter = self.iter.split()?;
+        self.metrics.branches.fetch_add(1, Ordering::Relaxed);
+        self.metrics.splits.fetch_add(1, Ordering::Relaxed);
+        Some(Self {
+            iter,
+            metrics: self.metrics.clone(),
+            pending: 0,
+            started: true,
+        })
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+impl<T> Drop for MetricsSink<T> {
+    fn drop(&mut self) {
+        self.flush();
+
+        // The last branch out stops the monitor, and waits for its final report
+        if self.metrics.branches.fetch_sub(1, Ordering::AcqRel) == 1 {
+            *self.metrics.done.lock().unwrap() = true;
+            self.metrics.wake.notify_all();
+            if let Some(monitor) = self.metrics.monitor.lock().unwrap().take() {
+                let _ = monitor.join();
+            }
+        }
+    }
+}
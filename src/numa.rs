@@ -0,0 +1,279 @@
+//! Best-effort NUMA affinity for worker threads.
+
+use crate::Spliterator;
+
+use std::fmt::{self, Debug, Formatter};
+
+/// A [Spliterator] that keeps each branch on the NUMA node of the branch it split from.
+///
+/// Created by [`ParSpliter::with_numa_affinity()`](crate::ParSpliter::with_numa_affinity).
+pub struct NumaAffinity<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The node this branch runs on, inherited from its parent until it starts.
+    node: Option<usize>,
+    /// Whether this branch has started running yet.
+    started: bool,
+    /// The pin on the current thread, undone when the branch is dropped.
+    pin: Option<Pin>,
+}
+
+impl<T> NumaAffinity<T> {
+    pub(crate) fn new(iter: T) -> Self {
+        Self::with_node(iter, None)
+    }
+
+    /// Create a branch that prefers to run on the given node.
+    fn with_node(iter: T, node: Option<usize>) -> Self {
+        Self {
+            iter,
+            node,
+            started: false,
+            pin: None,
+        }
+    }
+
+    /// Pin the current thread the first time this branch runs.
+    fn start(&mut self) {
+        if !self.started {
+            self.started = true;
+            let (node, pin) = Pin::enter(self.node);
+            self.node = node;
+            self.pin = pin;
+        }
+    }
+}
+
+impl<T: Clone> Clone for NumaAffinity<T> {
+    fn clone(&self) -> Self {
+        // The clone hasn't started, so it doesn't share our pin
+        Self::with_node(self.iter.clone(), self.node)
+    }
+}
+
+impl<T: Debug> Debug for NumaAffinity<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NumaAffinity")
+            .field("iter", &self.iter)
+            .field("node", &self.node)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Iterator> Iterator for NumaAffinity<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.start();
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for NumaAffinity<T> {
+    fn split(&mut self) -> Option<Self> {
+        // Find out where we are first, so the split-off half knows where to go
+        self.start();
+        self.iter
+            .split()
+            .map(|iter| Self::with_node(iter, self.node))
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+/// Pick the node for a branch to run on.
+///
+/// If an enclosing branch has already pinned the thread, the branch was stolen onto it, so it
+/// migrates to that node.  Otherwise, it stays on the node it inherited, and the root branch
+/// starts on the node it happens to be running on.
+#[cfg(any(target_os = "linux", test))]
+fn choose_node(
+    inherited: Option<usize>,
+    pinned: Option<usize>,
+    current: Option<usize>,
+) -> Option<usize> {
+    pinned.or(inherited).or(current)
+}
+
+#[cfg(target_os = "linux")]
+thread_local! {
+    /// The node this thread is pinned to by a running branch, if any.
+    static PINNED: std::cell::Cell<Option<usize>> = const { std::cell::Cell::new(None) };
+}
+
+/// A worker thread pinned to the CPUs of a node, which gets its old CPUs back when dropped.
+#[cfg(target_os = "linux")]
+struct Pin {
+    /// The thread we pinned.
+    thread: std::thread::ThreadId,
+    /// Its affinity from before.
+    old: libc::cpu_set_t,
+}
+
+#[cfg(target_os = "linux")]
+impl Pin {
+    /// Pin the current thread for a branch that inherited the given node.
+    ///
+    /// Returns the node the branch ended up on, and the pin if we made one.  Threads that are
+    /// already pinned, and threads outside of a Rayon pool, are left alone.
+    fn enter(inherited: Option<usize>) -> (Option<usize>, Option<Self>) {
+        if rayon::current_thread_index().is_none() {
+            return (inherited, None);
+        }
+
+        let pinned = PINNED.get();
+        let node = choose_node(inherited, pinned, current_node());
+        if pinned.is_some() {
+            return (node, None);
+        }
+        let Some(cpus) = node.and_then(|node| node_cpus().get(node)) else {
+            return (node, None);
+        };
+
+        let size = std::mem::size_of::<libc::cpu_set_t>();
+        let old = unsafe {
+            let mut old: libc::cpu_set_t = std::mem::zeroed();
+            if libc::sched_getaffinity(0, size, &mut old) != 0 {
+                return (node, None);
+            }
+
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            for &cpu in cpus {
+                if cpu < libc::CPU_SETSIZE as usize {
+                    libc::CPU_SET(cpu, &mut set);
+                }
+            }
+            // If this fails, the thread just stays unpinned
+            if libc::sched_setaffinity(0, size, &set) != 0 {
+                return (node, None);
+            }
+            old
+        };
+
+        PINNED.set(node);
+        let thread = std::thread::current().id();
+        (node, Some(Self { thread, old }))
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for Pin {
+    fn drop(&mut self) {
+        // Branches finish on the thread they started on, but don't unpin anyone else if not
+        if std::thread::current().id() == self.thread {
+            let size = std::mem::size_of_val(&self.old);
+            unsafe {
+                libc::sched_setaffinity(0, size, &self.old);
+            }
+            PINNED.set(None);
+        }
+    }
+}
+
+/// Pinning is only implemented on Linux.
+#[cfg(not(target_os = "linux"))]
+enum Pin {}
+
+#[cfg(not(target_os = "linux"))]
+impl Pin {
+    fn enter(inherited: Option<usize>) -> (Option<usize>, Option<Self>) {
+        (inherited, None)
+    }
+}
+
+/// Get the CPUs the current thread may run on.
+#[cfg(all(target_os = "linux", test))]
+pub(crate) fn current_cpus() -> Vec<usize> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::sched_getaffinity(0, std::mem::size_of_val(&set), &mut set);
+        (0..libc::CPU_SETSIZE as usize)
+            .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+            .collect()
+    }
+}
+
+/// Get the node of the CPU we're running on.
+#[cfg(target_os = "linux")]
+fn current_node() -> Option<usize> {
+    let cpu = usize::try_from(unsafe { libc::sched_getcpu() }).ok()?;
+    node_cpus().iter().position(|cpus| cpus.contains(&cpu))
+}
+
+/// Get the CPUs of each NUMA node.
+#[cfg(target_os = "linux")]
+fn node_cpus() -> &'static [Vec<usize>] {
+    use std::fs;
+    use std::sync::OnceLock;
+
+    static NODES: OnceLock<Vec<Vec<usize>>> = OnceLock::new();
+
+    NODES.get_or_init(|| {
+        let Ok(entries) = fs::read_dir("/sys/devices/system/node") else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("node"))
+            .filter_map(|entry| fs::read_to_string(entry.path().join("cpulist")).ok())
+            .map(|list| parse_cpu_list(list.trim()))
+            .collect()
+    })
+}
+
+/// Parse a Linux CPU list like `0-3,8-11`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((lo, hi)) => Some(lo.parse().ok()?..=hi.parse().ok()?),
+            None => {
+                let cpu = range.parse().ok()?;
+                Some(cpu..=cpu)
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11"), [0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list(""), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_choose_node() {
+        // The root starts wherever it is
+        assert_eq!(choose_node(None, None, Some(2)), Some(2));
+        // Children stay on their parent's node
+        assert_eq!(choose_node(Some(1), None, Some(2)), Some(1));
+        // Unless they were stolen onto a thread that's pinned elsewhere
+        assert_eq!(choose_node(Some(1), Some(3), Some(2)), Some(3));
+    }
+
+    #[test]
+    fn test_split_inherits_node() {
+        let items: Vec<_> = (0..10).collect();
+        let mut branch = NumaAffinity::with_node(items.iter(), Some(1));
+        let child = branch.split().unwrap();
+        assert_eq!(branch.node, Some(1));
+        assert_eq!(child.node, Some(1));
+        assert!(!child.started);
+    }
+}
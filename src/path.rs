@@ -0,0 +1,205 @@
+//! Tracking the path through the split tree.
+
+use crate::Spliterator;
+
+/// One step along a path through the split tree.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Side {
+    /// The branch that kept going after a split.
+    Left,
+    /// The branch that was split off.
+    Right,
+}
+
+/// A [Spliterator] that tags each item with the path to the branch that produced it.
+///
+/// Created by [`ParSpliter::with_branch_path()`](crate::ParSpliter::with_branch_path).
+#[derive(Clone, Debug)]
+pub struct BranchPath<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The path from the root to this branch.
+    path: Vec<Side>,
+}
+
+impl<T> BranchPath<T> {
+    pub(crate) fn new(iter: T) -> Self {
+        Self {
+            iter,
+            path: Vec::new(),
+        }
+    }
+}
+
+impl<T: Iterator> Iterator for BranchPath<T> {
+    type Item = (Vec<Side>, T::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|item| (self.path.clone(), item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for BranchPath<T> {
+    fn split(&mut self) -> Option<Self> {
+        let iter = self.iter.split()?;
+
+        let mut path = self.path.clone();
+        path.push(Side::Right);
+        self.path.push(Side::Left);
+
+        Some(Self { iter, path })
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+/// A compact identifier for a branch, derived from its path through the split tree.
+///
+/// The root is `1`, and each split appends a bit for the [Side] of each branch, so different
+/// branches have different IDs.  Only [`MAX_DEPTH`](Self::MAX_DEPTH) steps of the path fit, so
+/// the adapters that hand out IDs stop splitting branches that deep, rather than let their
+/// children share an ID.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BranchId(u64);
+
+impl BranchId {
+    /// The ID of the root branch.
+    pub(crate) const ROOT: Self = Self(1);
+
+    /// The deepest a branch can be nested and still have a unique ID.
+    pub const MAX_DEPTH: u32 = u64::BITS - 1;
+
+    /// Get the ID of a child branch, if it fits.
+    pub(crate) fn child(self, side: Side) -> Option<Self> {
+        if self.depth() >= Self::MAX_DEPTH {
+            return None;
+        }
+
+        let bit = match side {
+            Side::Left => 0,
+            Side::Right => 1,
+        };
+        Some(Self(self.0 << 1 | bit))
+    }
+
+    /// Get the number of splits between the root and this branch.
+    pub fn depth(self) -> u32 {
+        u64::BITS - 1 - self.0.leading_zeros()
+    }
+
+    /// Get the path from the root to this branch.
+    pub fn path(self) -> Vec<Side> {
+        (0..self.depth())
+            .rev()
+            .map(|i| {
+                if self.0 >> i & 1 == 0 {
+                    Side::Left
+                } else {
+                    Side::Right
+                }
+            })
+            .collect()
+    }
+}
+
+/// A [Spliterator] that tags each item with its branch and its index within that branch.
+///
+/// Created by [`ParSpliter::for_each_with_location()`](crate::ParSpliter::for_each_with_location).
+#[derive(Clone, Debug)]
+pub(crate) struct BranchLocation<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The ID of this branch.
+    id: BranchId,
+    /// The index of the next item in this branch.
+    count: usize,
+}
+
+impl<T> BranchLocation<T> {
+    pub(crate) fn new(iter: T) -> Self {
+        Self {
+            iter,
+            id: BranchId::ROOT,
+            count: 0,
+        }
+    }
+}
+
+impl<T: Iterator> Iterator for BranchLocation<T> {
+    type Item = (BranchId, usize, T::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let i = self.count;
+        self.count += 1;
+        Some((self.id, i, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for BranchLocation<T> {
+    fn split(&mut self) -> Option<Self> {
+        let left = self.id.child(Side::Left)?;
+        let right = self.id.child(Side::Right)?;
+        let iter = self.iter.split()?;
+
+        // The rest of this branch becomes a new branch, so start counting again
+        let split = Self {
+            iter,
+            id: right,
+            count: 0,
+        };
+        self.id = left;
+        self.count = 0;
+
+        Some(split)
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_id_path() {
+        use Side::*;
+
+        let id = BranchId::ROOT.child(Left).unwrap();
+        let id = id.child(Right).unwrap().child(Right).unwrap();
+        assert_eq!(id.depth(), 3);
+        assert_eq!(id.path(), [Left, Right, Right]);
+        assert_eq!(BranchId::ROOT.path(), []);
+    }
+
+    #[test]
+    fn test_branch_id_max_depth() {
+        let mut id = BranchId::ROOT;
+        for _ in 0..BranchId::MAX_DEPTH {
+            id = id.child(Side::Right).unwrap();
+        }
+        assert_eq!(id.depth(), BranchId::MAX_DEPTH);
+        assert_eq!(id.path(), vec![Side::Right; BranchId::MAX_DEPTH as usize]);
+        assert_eq!(id.child(Side::Left), None);
+    }
+}
@@ -0,0 +1,82 @@
+//! Pausing a run and keeping what's left.
+
+use crate::Spliterator;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A [Spliterator] that stops when a shared flag is set, saving its remaining work.
+///
+/// Used by [`ParSpliter::peek_frontier()`](crate::ParSpliter::peek_frontier).
+#[derive(Debug)]
+pub(crate) struct Pausable<T> {
+    /// The underlying Spliterator, until we're paused.
+    iter: Option<T>,
+    /// The pause flag.
+    pause: Arc<AtomicBool>,
+    /// Where paused branches leave their Spliterators.
+    frontier: Arc<Mutex<Vec<T>>>,
+}
+
+impl<T> Pausable<T> {
+    pub(crate) fn new(iter: T, pause: Arc<AtomicBool>, frontier: Arc<Mutex<Vec<T>>>) -> Self {
+        Self {
+            iter: Some(iter),
+            pause,
+            frontier,
+        }
+    }
+
+    /// Check whether we've been paused, saving the rest of the branch if so.
+    fn paused(&mut self) -> bool {
+        if !self.pause.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        if let Some(iter) = self.iter.take() {
+            self.frontier.lock().unwrap().push(iter);
+        }
+        true
+    }
+}
+
+impl<T: Iterator> Iterator for Pausable<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.paused() {
+            None
+        } else {
+            self.iter.as_mut()?.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            Some(iter) => (0, iter.size_hint().1),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<T: Spliterator> Spliterator for Pausable<T> {
+    fn split(&mut self) -> Option<Self> {
+        if self.paused() {
+            return None;
+        }
+
+        let split = self.iter.as_mut()?.split()?;
+        Some(Self::new(split, self.pause.clone(), self.frontier.clone()))
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        match &self.iter {
+            Some(iter) => iter.max_items_per_branch(),
+            None => Some(0),
+        }
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.as_ref().is_some_and(T::should_split)
+    }
+}
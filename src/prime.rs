@@ -0,0 +1,119 @@
+//! Expanding the frontier before going parallel.
+
+use crate::Spliterator;
+
+use std::fmt::{self, Debug, Formatter};
+
+/// A [Spliterator] whose root has been expanded into a frontier of independent sub-iterators.
+///
+/// Created by [`ParSpliter::prime()`](crate::ParSpliter::prime).
+pub struct Primed<T: Iterator> {
+    /// The items that were consumed while expanding the frontier.
+    items: Vec<T::Item>,
+    /// The independent sub-iterators.
+    frontier: Vec<T>,
+}
+
+impl<T: Spliterator> Primed<T> {
+    pub(crate) fn new(iter: T, target: usize) -> Self {
+        let mut items = Vec::new();
+        let mut frontier = vec![iter];
+
+        while frontier.len() < target {
+            if let Some(split) = frontier.iter_mut().find_map(T::split) {
+                frontier.push(split);
+                continue;
+            }
+
+            // Nothing can split yet, so consume an item to make progress
+            let Some(iter) = frontier.last_mut() else {
+                break;
+            };
+            if let Some(item) = iter.next() {
+                items.push(item);
+            } else {
+                frontier.pop();
+            }
+        }
+
+        Self { items, frontier }
+    }
+}
+
+impl<T> Debug for Primed<T>
+where
+    T: Iterator + Debug,
+    T::Item: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Primed")
+            .field("items", &self.items)
+            .field("frontier", &self.frontier)
+            .finish()
+    }
+}
+
+impl<T: Iterator> Iterator for Primed<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.items.pop() {
+            return Some(item);
+        }
+
+        while let Some(iter) = self.frontier.last_mut() {
+            if let Some(item) = iter.next() {
+                return Some(item);
+            }
+            self.frontier.pop();
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let mut lo = self.items.len();
+        let mut hi = Some(lo);
+        for iter in &self.frontier {
+            let (a, b) = iter.size_hint();
+            lo = lo.saturating_add(a);
+            hi = hi.zip(b).and_then(|(hi, b)| hi.checked_add(b));
+        }
+        (lo, hi)
+    }
+}
+
+impl<T: Spliterator> Spliterator for Primed<T> {
+    fn split(&mut self) -> Option<Self> {
+        let len = self.frontier.len();
+        if len >= 2 {
+            // Hand off half of the frontier at once
+            let frontier = self.frontier.split_off(len / 2);
+            Some(Self {
+                items: Vec::new(),
+                frontier,
+            })
+        } else if let Some(iter) = self.frontier.last_mut() {
+            iter.split().map(|split| Self {
+                items: Vec::new(),
+                frontier: vec![split],
+            })
+        } else {
+            None
+        }
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.frontier.iter().try_fold(self.items.len(), |n, iter| {
+            n.checked_add(iter.max_items_per_branch()?)
+        })
+    }
+
+    fn should_split(&self) -> bool {
+        match self.frontier.as_slice() {
+            [] => false,
+            [iter] => iter.should_split(),
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Cartesian products.
+
+use crate::Spliterator;
+
+use std::sync::Arc;
+
+/// A [Spliterator] over the Cartesian product of two sets.
+///
+/// Splits bisect the outer set, while the inner set is shared between all the splits.
+#[derive(Clone, Debug)]
+pub struct ProductSpliter<A, B> {
+    /// The outer items.  The current row is `outer[row]`.
+    outer: Vec<A>,
+    /// The index of the current row.
+    row: usize,
+    /// The inner items, shared by every split.
+    inner: Arc<[B]>,
+    /// The index of the next column in the current row.
+    col: usize,
+}
+
+impl<A, B> ProductSpliter<A, B> {
+    /// Create a spliterator over every pair `(a, b)` with `a` from `outer` and `b` from `inner`.
+    pub fn new(outer: Vec<A>, inner: Vec<B>) -> Self {
+        Self {
+            outer,
+            row: 0,
+            inner: inner.into(),
+            col: 0,
+        }
+    }
+}
+
+impl<A: Clone, B: Clone> Iterator for ProductSpliter<A, B> {
+    type Item = (A, B);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.inner.is_empty() {
+            return None;
+        }
+
+        let a = self.outer.get(self.row)?;
+        let b = &self.inner[self.col];
+        let item = (a.clone(), b.clone());
+
+        self.col += 1;
+        if self.col == self.inner.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rows = self.outer.len() - self.row;
+        let len = (rows * self.inner.len()).saturating_sub(self.col);
+        (len, Some(len))
+    }
+}
+
+impl<A: Clone, B: Clone> Spliterator for ProductSpliter<A, B> {
+    fn split(&mut self) -> Option<Self> {
+        // Keep the current row, which may be partially consumed, and hand off
+        // half of the rest
+        let rows = self.outer.len() - self.row;
+        if rows >= 2 && !self.inner.is_empty() {
+            let mid = self.row + rows / 2;
+            Some(Self {
+                outer: self.outer.split_off(mid),
+                row: 0,
+                inner: self.inner.clone(),
+                col: 0,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    #[test]
+    fn test_par_split() {
+        let a: Vec<u32> = (0..300).collect();
+        let b: Vec<char> = ('a'..='z').collect();
+
+        let mut expected: Vec<_> = ProductSpliter::new(a.clone(), b.clone()).collect();
+        assert_eq!(expected.len(), a.len() * b.len());
+        expected.sort_unstable();
+
+        let mut actual: Vec<_> = ProductSpliter::new(a, b).par_split().collect();
+        actual.sort_unstable();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_size_hint() {
+        let mut iter = ProductSpliter::new(vec![1, 2, 3], vec![4, 5]);
+        assert_eq!(iter.size_hint(), (6, Some(6)));
+
+        iter.next();
+        let split = iter.split().unwrap();
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+        assert_eq!(split.size_hint(), (4, Some(4)));
+    }
+}
@@ -0,0 +1,81 @@
+//! Progress reporting.
+
+use crate::Spliterator;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A [Spliterator] that counts the items it produces into a shared counter.
+///
+/// Created by [`ParSpliter::with_progress()`](crate::ParSpliter::with_progress).
+#[derive(Debug)]
+pub struct Progress<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The shared counter.
+    counter: Arc<AtomicU64>,
+    /// The number of items produced since we last updated the counter.
+    pending: u64,
+}
+
+impl<T> Progress<T> {
+    /// The number of items to produce between updates of the shared counter.
+    const BATCH: u64 = 256;
+
+    pub(crate) fn new(iter: T, counter: Arc<AtomicU64>) -> Self {
+        Self {
+            iter,
+            counter,
+            pending: 0,
+        }
+    }
+
+    /// Add the pending items to the shared counter.
+    fn flush(&mut self) {
+        if self.pending > 0 {
+            self.counter.fetch_add(self.pending, Ordering::Relaxed);
+            self.pending = 0;
+        }
+    }
+}
+
+impl<T: Iterator> Iterator for Progress<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next();
+        if item.is_some() {
+            self.pending += 1;
+            if self.pending == Self::BATCH {
+                self.flush();
+            }
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for Progress<T> {
+    fn split(&mut self) -> Option<Self> {
+        self.iter
+            .split()
+            .map(|iter| Self::new(iter, self.counter.clone()))
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+impl<T> Drop for Progress<T> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
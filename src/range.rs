@@ -0,0 +1,173 @@
+//! Splitting ranges.
+
+use crate::Spliterator;
+
+use std::ops::RangeInclusive;
+
+/// A [Spliterator] over an inclusive range of integers, like `a..=b`.
+///
+/// [RangeInclusive] can't implement [Spliterator] directly, since its exhaustion state is private.
+/// This type tracks it explicitly, so that ranges ending at the maximum value of their type can be
+/// split without overflowing, skipping, or duplicating an endpoint.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RangeInclusiveSpliter<T> {
+    /// The next item.
+    start: T,
+    /// The last item.
+    end: T,
+    /// Whether the range has run out.
+    exhausted: bool,
+}
+
+impl<T: PartialOrd> RangeInclusiveSpliter<T> {
+    /// Create a spliterator over `start..=end`.
+    pub fn new(start: T, end: T) -> Self {
+        (start..=end).into()
+    }
+}
+
+impl<T: PartialOrd> From<RangeInclusive<T>> for RangeInclusiveSpliter<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let exhausted = range.is_empty();
+        let (start, end) = range.into_inner();
+        Self {
+            start,
+            end,
+            exhausted,
+        }
+    }
+}
+
+macro_rules! range_inclusive_spliterator {
+    ($($t:ty => $u:ty),*) => {$(
+        impl Iterator for RangeInclusiveSpliter<$t> {
+            type Item = $t;
+
+            fn next(&mut self) -> Option<$t> {
+                if self.exhausted {
+                    return None;
+                }
+
+                let next = self.start;
+                if self.start == self.end {
+                    self.exhausted = true;
+                } else {
+                    self.start += 1;
+                }
+                Some(next)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                if self.exhausted {
+                    return (0, Some(0));
+                }
+
+                let len = (self.end as $u).wrapping_sub(self.start as $u);
+                match usize::try_from(len).ok().and_then(|n| n.checked_add(1)) {
+                    Some(n) => (n, Some(n)),
+                    None => (usize::MAX, None),
+                }
+            }
+        }
+
+        impl Spliterator for RangeInclusiveSpliter<$t> {
+            fn split(&mut self) -> Option<Self> {
+                if self.exhausted || self.start == self.end {
+                    return None;
+                }
+
+                // mid < end, so mid + 1 can't overflow
+                let len = (self.end as $u).wrapping_sub(self.start as $u);
+                let mid = self.start.wrapping_add((len / 2) as $t);
+                let split = Self::new(mid + 1, self.end);
+                self.end = mid;
+                Some(split)
+            }
+        }
+    )*};
+}
+
+range_inclusive_spliterator! {
+    u8 => u8,
+    u16 => u16,
+    u32 => u32,
+    u64 => u64,
+    u128 => u128,
+    usize => usize,
+    i8 => u8,
+    i16 => u16,
+    i32 => u32,
+    i64 => u64,
+    i128 => u128,
+    isize => usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    /// Split a spliterator as much as possible, and collect all its items.
+    fn split_all<T: Spliterator>(mut iter: T) -> Vec<T::Item> {
+        if let Some(split) = iter.split() {
+            let mut items = split_all(iter);
+            items.extend(split_all(split));
+            items
+        } else {
+            iter.collect()
+        }
+    }
+
+    #[test]
+    fn test_range_inclusive() {
+        for a in 0..=16u8 {
+            for b in 0..=16u8 {
+                let expected: Vec<_> = (a..=b).collect();
+                assert_eq!(split_all(RangeInclusiveSpliter::from(a..=b)), expected);
+                assert_eq!(RangeInclusiveSpliter::new(a, b).count(), expected.len());
+            }
+        }
+
+        let mut zero = RangeInclusiveSpliter::new(0u32, 0);
+        assert_eq!(zero.split(), None);
+        assert_eq!(zero.size_hint(), (1, Some(1)));
+        assert_eq!(zero.collect::<Vec<_>>(), [0]);
+
+        let mut one = RangeInclusiveSpliter::new(0u32, 1);
+        assert_eq!(one.split(), Some(RangeInclusiveSpliter::new(1, 1)));
+        assert_eq!(one.collect::<Vec<_>>(), [0]);
+
+        let all: Vec<_> = (u8::MIN..=u8::MAX).collect();
+        assert_eq!(
+            split_all(RangeInclusiveSpliter::from(u8::MIN..=u8::MAX)),
+            all
+        );
+
+        let all: Vec<_> = (i8::MIN..=i8::MAX).collect();
+        assert_eq!(
+            split_all(RangeInclusiveSpliter::from(i8::MIN..=i8::MAX)),
+            all
+        );
+
+        let top = RangeInclusiveSpliter::new(usize::MAX - 1000, usize::MAX);
+        assert_eq!(top.size_hint(), (1001, Some(1001)));
+        assert_eq!(top.par_split().count(), 1001);
+        assert_eq!(
+            RangeInclusiveSpliter::new(0, usize::MAX).size_hint(),
+            (usize::MAX, None)
+        );
+
+        let mut exhausted = 0..=0;
+        exhausted.next();
+        assert_eq!(RangeInclusiveSpliter::from(exhausted).count(), 0);
+    }
+
+    #[test]
+    fn test_par_split() {
+        let sum: u64 = RangeInclusiveSpliter::new(0u64, 99_999).par_split().sum();
+        assert_eq!(sum, 100_000 * 99_999 / 2);
+    }
+}
@@ -0,0 +1,96 @@
+//! Limiting the global rate of items.
+
+use crate::Spliterator;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A schedule of evenly spaced slots shared by every branch.
+#[derive(Debug)]
+struct Schedule {
+    /// When the schedule was created.
+    start: Instant,
+    /// The time between slots, in nanoseconds.
+    interval: u64,
+    /// The next free slot, in nanoseconds since `start`.
+    next: AtomicU64,
+}
+
+impl Schedule {
+    /// Reserve the next slot, and sleep until it comes up.
+    fn wait(&self) {
+        let now = self.start.elapsed().as_nanos() as u64;
+
+        // Slots in the past are forfeited, so an idle period doesn't allow a burst later
+        let slot = self
+            .next
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |next| {
+                Some(next.max(now) + self.interval)
+            })
+            .unwrap()
+            .max(now);
+
+        if slot > now {
+            thread::sleep(Duration::from_nanos(slot - now));
+        }
+    }
+}
+
+/// A [Spliterator] that caps the rate of items across all of its branches.
+///
+/// Created by [`ParSpliter::with_rate_limit()`](crate::ParSpliter::with_rate_limit).
+#[derive(Clone, Debug)]
+pub struct RateLimit<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The schedule shared by every split.
+    schedule: Arc<Schedule>,
+}
+
+impl<T> RateLimit<T> {
+    pub(crate) fn new(iter: T, per_sec: u32) -> Self {
+        assert!(per_sec != 0, "rate must be nonzero");
+
+        Self {
+            iter,
+            schedule: Arc::new(Schedule {
+                start: Instant::now(),
+                interval: 1_000_000_000 / per_sec as u64,
+                next: AtomicU64::new(0),
+            }),
+        }
+    }
+}
+
+impl<T: Iterator> Iterator for RateLimit<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        self.schedule.wait();
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for RateLimit<T> {
+    fn split(&mut self) -> Option<Self> {
+        self.iter.split().map(|iter| Self {
+            iter,
+            schedule: self.schedule.clone(),
+        })
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
@@ -0,0 +1,50 @@
+//! Reversing the order within each branch.
+
+use crate::Spliterator;
+
+/// A [Spliterator] that consumes each branch from the back.
+///
+/// Created by [`ParSpliter::rev_branch()`](crate::ParSpliter::rev_branch).
+#[derive(Clone, Debug)]
+pub struct RevBranch<T> {
+    /// The underlying Spliterator.
+    iter: T,
+}
+
+impl<T> RevBranch<T> {
+    pub(crate) fn new(iter: T) -> Self {
+        Self { iter }
+    }
+}
+
+impl<T: DoubleEndedIterator> Iterator for RevBranch<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: DoubleEndedIterator> DoubleEndedIterator for RevBranch<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl<T: Spliterator + DoubleEndedIterator> Spliterator for RevBranch<T> {
+    fn split(&mut self) -> Option<Self> {
+        self.iter.split().map(Self::new)
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
@@ -0,0 +1,109 @@
+//! Grouping runs of sorted data.
+
+use crate::Spliterator;
+
+/// A [Spliterator] over the runs of equal items in a sorted slice.
+///
+/// Each item is a key paired with the length of its run.  Splits always happen at a run
+/// boundary, so every run is counted exactly once, by a single branch.  If the slice is not
+/// sorted, the results are unspecified (but safe).
+#[derive(Clone, Copy, Debug)]
+pub struct RunLengthSpliter<'a, T> {
+    /// The unconsumed part of the slice.
+    data: &'a [T],
+}
+
+impl<'a, T: Ord> RunLengthSpliter<'a, T> {
+    /// Create a spliterator over the runs of the sorted slice `data`.
+    pub fn new(data: &'a [T]) -> Self {
+        Self { data }
+    }
+
+    /// Find the end of the run that starts at `data[i]`.
+    fn run_end(&self, i: usize) -> usize {
+        let key = &self.data[i];
+        i + self.data[i..].partition_point(|x| x <= key)
+    }
+
+    /// Find the run boundary nearest to the midpoint of the slice.
+    fn split_point(&self) -> Option<usize> {
+        let len = self.data.len();
+        let mid = len / 2;
+        if mid == 0 {
+            return None;
+        }
+
+        // Find the run that contains the midpoint, and pick whichever of its
+        // ends is closer, as long as it leaves something on both sides
+        let key = &self.data[mid];
+        let start = self.data[..mid].partition_point(|x| x < key);
+        let end = self.run_end(mid);
+
+        let candidates = [start, end].into_iter().filter(|&i| i > 0 && i < len);
+        candidates.min_by_key(|&i| i.abs_diff(mid))
+    }
+}
+
+impl<'a, T: Ord + Clone> Iterator for RunLengthSpliter<'a, T> {
+    type Item = (T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.data.first()?.clone();
+        let end = self.run_end(0);
+        self.data = &self.data[end..];
+        Some((key, end))
+    }
+}
+
+impl<'a, T: Ord + Clone> Spliterator for RunLengthSpliter<'a, T> {
+    fn split(&mut self) -> Option<Self> {
+        let i = self.split_point()?;
+        let (left, right) = self.data.split_at(i);
+        self.data = left;
+        Some(Self::new(right))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    /// Group the runs of a slice the simple way.
+    fn group(data: &[u32]) -> Vec<(u32, usize)> {
+        let mut runs: Vec<(u32, usize)> = Vec::new();
+        for &n in data {
+            match runs.last_mut() {
+                Some((key, count)) if *key == n => *count += 1,
+                _ => runs.push((n, 1)),
+            }
+        }
+        runs
+    }
+
+    #[test]
+    fn test_split_point() {
+        let data = [1, 1, 1, 2, 2, 3, 3, 3, 3, 3];
+        let mut left = RunLengthSpliter::new(&data);
+        let right = left.split().unwrap();
+        assert_eq!(left.data, [1, 1, 1, 2, 2]);
+        assert_eq!(right.data, [3, 3, 3, 3, 3]);
+
+        assert!(RunLengthSpliter::new(&[7, 7, 7]).split().is_none());
+        assert!(RunLengthSpliter::<u32>::new(&[]).split().is_none());
+    }
+
+    #[test]
+    fn test_par_split() {
+        let data: Vec<u32> = (0..100_000).map(|n: u32| n.isqrt()).collect();
+        let expected = group(&data);
+        assert_eq!(RunLengthSpliter::new(&data).collect::<Vec<_>>(), expected);
+
+        let mut actual: Vec<_> = RunLengthSpliter::new(&data).par_split().collect();
+        actual.sort_unstable();
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,65 @@
+//! Per-branch sampling.
+
+use crate::Spliterator;
+
+/// A [Spliterator] that keeps every `k`th item within each branch.
+///
+/// Created by [`ParSpliter::sample_every_branch()`](crate::ParSpliter::sample_every_branch).
+#[derive(Clone, Debug)]
+pub struct SampleEvery<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The sampling period.
+    k: usize,
+    /// The number of items seen since the last one we kept.
+    skipped: usize,
+}
+
+impl<T> SampleEvery<T> {
+    pub(crate) fn new(iter: T, k: usize) -> Self {
+        assert!(k != 0, "sampling period must be nonzero");
+        Self {
+            iter,
+            k,
+            skipped: 0,
+        }
+    }
+}
+
+impl<T: Iterator> Iterator for SampleEvery<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            let keep = self.skipped == 0;
+            self.skipped = (self.skipped + 1) % self.k;
+            if keep {
+                return Some(item);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // The number of items to skip before the next one we keep
+        let first = (self.k - self.skipped) % self.k;
+        let kept = |n: usize| n.checked_sub(first + 1).map_or(0, |n| n / self.k + 1);
+        let (lo, hi) = self.iter.size_hint();
+        (kept(lo), hi.map(kept))
+    }
+}
+
+impl<T: Spliterator> Spliterator for SampleEvery<T> {
+    fn split(&mut self) -> Option<Self> {
+        // The new branch starts counting from scratch
+        self.iter.split().map(|iter| Self::new(iter, self.k))
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
@@ -0,0 +1,138 @@
+//! Capturing and replaying split schedules.
+
+use crate::{BranchId, Side, Spliterator};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A record of where every branch of a run split, from
+/// [`ParSpliter::capture_schedule()`](crate::ParSpliter::capture_schedule).
+///
+/// Each branch splits at most once: afterwards, both halves are new branches.  So the whole
+/// schedule is just the number of items each branch consumed before it split, if it did.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SplitSchedule {
+    /// The split point of each branch that split.
+    splits: HashMap<BranchId, usize>,
+}
+
+impl SplitSchedule {
+    /// Get the number of splits in the schedule.
+    pub fn split_count(&self) -> usize {
+        self.splits.len()
+    }
+
+    /// Get the number of items a branch consumed before splitting, if it split at all.
+    pub fn split_point(&self, id: BranchId) -> Option<usize> {
+        self.splits.get(&id).copied()
+    }
+}
+
+/// Whether we're capturing or replaying a schedule.
+#[derive(Clone, Debug)]
+enum Mode {
+    /// Record the splits into a shared schedule.
+    Capture(Arc<Mutex<SplitSchedule>>),
+    /// Split only where the schedule says to.
+    Replay(Arc<SplitSchedule>),
+}
+
+/// A [Spliterator] that captures or replays a [SplitSchedule].
+///
+/// Created by [`ParSpliter::capture_schedule()`](crate::ParSpliter::capture_schedule) and
+/// [`ParSpliter::replay()`](crate::ParSpliter::replay).
+#[derive(Clone, Debug)]
+pub struct Scheduled<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The ID of this branch.
+    id: BranchId,
+    /// The number of items this branch has consumed.
+    consumed: usize,
+    /// What to do with the schedule.
+    mode: Mode,
+}
+
+impl<T> Scheduled<T> {
+    /// Start capturing a schedule.
+    pub(crate) fn capture(iter: T) -> (Self, Arc<Mutex<SplitSchedule>>) {
+        let schedule = Arc::default();
+        let mode = Mode::Capture(Arc::clone(&schedule));
+        (Self::new(iter, mode), schedule)
+    }
+
+    /// Start replaying a schedule.
+    pub(crate) fn replay(iter: T, schedule: &SplitSchedule) -> Self {
+        Self::new(iter, Mode::Replay(Arc::new(schedule.clone())))
+    }
+
+    fn new(iter: T, mode: Mode) -> Self {
+        Self {
+            iter,
+            id: BranchId::ROOT,
+            consumed: 0,
+            mode,
+        }
+    }
+
+    /// Check whether the schedule allows splitting right now.
+    fn allowed(&self) -> bool {
+        match &self.mode {
+            Mode::Capture(_) => true,
+            Mode::Replay(schedule) => schedule.split_point(self.id) == Some(self.consumed),
+        }
+    }
+}
+
+impl<T: Iterator> Iterator for Scheduled<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        self.consumed += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for Scheduled<T> {
+    fn split(&mut self) -> Option<Self> {
+        if !self.allowed() {
+            return None;
+        }
+
+        let left = self.id.child(Side::Left)?;
+        let right = self.id.child(Side::Right)?;
+        let iter = self.iter.split()?;
+        if let Mode::Capture(schedule) = &self.mode {
+            schedule
+                .lock()
+                .unwrap()
+                .splits
+                .insert(self.id, self.consumed);
+        }
+
+        // Both halves are new branches
+        let split = Self {
+            iter,
+            id: right,
+            consumed: 0,
+            mode: self.mode.clone(),
+        };
+        self.id = left;
+        self.consumed = 0;
+
+        Some(split)
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.allowed() && self.iter.should_split()
+    }
+}
@@ -0,0 +1,138 @@
+//! A backend for running spliterators on scoped threads.
+
+use crate::Spliterator;
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// A branch waiting to run, and its remaining split budget.
+type Job<T> = (T, usize);
+
+/// The state shared by every worker.
+struct Shared<T> {
+    /// Each worker's queue of branches.
+    deques: Vec<Mutex<VecDeque<Job<T>>>>,
+    /// The number of branches that are queued or running.
+    pending: AtomicUsize,
+    /// Set if any worker panicked, so the others stop.
+    panicked: AtomicBool,
+}
+
+impl<T> Shared<T> {
+    /// Find a branch to run on worker `i`.
+    fn find_work(&self, i: usize) -> Option<Job<T>> {
+        // Take our own newest branch first
+        if let Some(job) = self.deques[i].lock().unwrap().pop_back() {
+            return Some(job);
+        }
+
+        // Otherwise, steal the oldest branch from someone else.  Like the Rayon bridge, a stolen
+        // branch gets a fresh split budget.
+        let n = self.deques.len();
+        (1..n)
+            .map(|j| (i + j) % n)
+            .find_map(|k| self.deques[k].lock().unwrap().pop_front())
+            .map(|(iter, _)| (iter, n))
+    }
+}
+
+/// Sets the panic flag if a worker unwinds.
+struct PanicGuard<'a>(&'a AtomicBool);
+
+impl Drop for PanicGuard<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Fold a [Spliterator] in parallel on `threads` scoped threads, without Rayon.
+///
+/// This runs the same continuing-split algorithm as [`par_split()`](crate::ParallelSpliterator),
+/// but on a set of [scoped threads](std::thread::scope) that share work through a simple
+/// work-stealing queue per thread.  Each thread folds every branch it runs into its own
+/// accumulator starting from `identity()`, and the accumulators are merged at the end.  Only the
+/// basic splitting strategy is supported; none of the [ParSpliter](crate::ParSpliter) options
+/// apply.  Threads that run out of work spin until everything is done, so this is only worth it
+/// for runs that keep every thread busy.
+pub fn par_split_scoped_threads<T, R, ID, F, M>(
+    iter: T,
+    threads: usize,
+    identity: ID,
+    fold: F,
+    merge: M,
+) -> R
+where
+    T: Spliterator + Send,
+    R: Send,
+    ID: Fn() -> R + Sync,
+    F: Fn(R, T::Item) -> R + Sync,
+    M: Fn(R, R) -> R,
+{
+    let threads = threads.max(1);
+    let shared = Shared {
+        deques: (0..threads).map(|_| Mutex::new(VecDeque::new())).collect(),
+        pending: AtomicUsize::new(1),
+        panicked: AtomicBool::new(false),
+    };
+    shared.deques[0].lock().unwrap().push_back((iter, threads));
+
+    let results: Vec<R> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|i| {
+                let (shared, identity, fold) = (&shared, &identity, &fold);
+                scope.spawn(move || worker(shared, i, identity(), fold))
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    results.into_iter().reduce(merge).unwrap_or_else(identity)
+}
+
+/// Run branches on worker `i` until there are none left.
+fn worker<T, R, F>(shared: &Shared<T>, i: usize, mut acc: R, fold: &F) -> R
+where
+    T: Spliterator,
+    F: Fn(R, T::Item) -> R,
+{
+    let _guard = PanicGuard(&shared.panicked);
+
+    while shared.pending.load(Ordering::Acquire) > 0 {
+        if shared.panicked.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let Some((mut iter, mut splits)) = shared.find_work(i) else {
+            thread::yield_now();
+            continue;
+        };
+
+        loop {
+            if splits > 0 && iter.should_split() {
+                if let Some(split) = iter.split() {
+                    splits /= 2;
+                    shared.pending.fetch_add(1, Ordering::Relaxed);
+                    shared.deques[i].lock().unwrap().push_back((split, splits));
+                    continue;
+                }
+            }
+
+            match iter.next() {
+                Some(item) => acc = fold(acc, item),
+                None => break,
+            }
+        }
+
+        shared.pending.fetch_sub(1, Ordering::Release);
+    }
+
+    acc
+}
@@ -0,0 +1,105 @@
+//! Splitting and filling slices.
+
+use crate::Spliterator;
+
+use std::mem;
+use std::slice;
+
+impl<'a, T> Spliterator for slice::Iter<'a, T> {
+    fn split(&mut self) -> Option<Self> {
+        let slice = self.as_slice();
+        let len = slice.len();
+        if len >= 2 {
+            let (left, right) = slice.split_at(len / 2);
+            *self = left.iter();
+            Some(right.iter())
+        } else {
+            None
+        }
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+/// A [Spliterator] that pairs each item with its slot in an output slice.
+///
+/// Created by [`ParSpliter::collect_into_slice()`](crate::ParSpliter::collect_into_slice).
+#[derive(Debug)]
+pub(crate) struct SliceFill<'a, T: Iterator> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The slots for the items this branch has yet to yield.
+    out: &'a mut [T::Item],
+}
+
+impl<'a, T: Iterator> SliceFill<'a, T> {
+    pub(crate) fn new(iter: T, out: &'a mut [T::Item]) -> Self {
+        Self { iter, out }
+    }
+}
+
+impl<'a, T: Iterator> Iterator for SliceFill<'a, T> {
+    type Item = (&'a mut T::Item, T::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.out.is_empty() {
+            return None;
+        }
+
+        let item = self.iter.next()?;
+        let (slot, rest) = mem::take(&mut self.out).split_first_mut()?;
+        self.out = rest;
+        Some((slot, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.out.len();
+        let (lo, hi) = self.iter.size_hint();
+        (lo.min(len), Some(hi.map_or(len, |hi| hi.min(len))))
+    }
+}
+
+impl<'a, T> Spliterator for SliceFill<'a, T>
+where
+    T: Spliterator + ExactSizeIterator,
+{
+    fn split(&mut self) -> Option<Self> {
+        let iter = self.iter.split()?;
+
+        // We keep the earlier items, so the split-off ones go after them
+        let (left, right) = mem::take(&mut self.out).split_at_mut(self.iter.len());
+        self.out = left;
+        Some(Self { iter, out: right })
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        Some(self.out.len())
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn test_slice_iter() {
+        let data: Vec<u32> = (0..10_000).collect();
+
+        // The items borrow from data, which outlives the run
+        let sum = AtomicU64::new(0);
+        data.iter().par_split().for_each(|&n| {
+            sum.fetch_add(u64::from(n), Ordering::Relaxed);
+        });
+        assert_eq!(sum.into_inner(), 10_000 * 9_999 / 2);
+    }
+}
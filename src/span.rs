@@ -0,0 +1,54 @@
+//! Spans for the `tracing` crate.
+
+use crate::Config;
+
+/// A span around one call to the bridge, entered until it's dropped.
+///
+/// Without the `tracing` feature, this does nothing and optimizes away entirely.
+#[derive(Debug)]
+pub(crate) struct BranchSpan {
+    /// The entered span, which is only held so it stays entered.
+    #[cfg(feature = "tracing")]
+    _entered: Option<tracing::span::EnteredSpan>,
+}
+
+impl BranchSpan {
+    /// Enter a span for a branch at the given depth, if the run is named.
+    #[allow(unused_variables)]
+    pub(crate) fn enter(config: &Config, depth: usize) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            _entered: config
+                .tracing_name
+                .map(|name| tracing::info_span!("branch", name, depth).entered()),
+        }
+    }
+}
+
+/// The span that was current when a branch split, for the half that may run on another thread.
+///
+/// Without the `tracing` feature, this does nothing and optimizes away entirely.
+#[derive(Debug)]
+pub(crate) struct ParentSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl ParentSpan {
+    /// Remember the current span.
+    pub(crate) fn current() -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::current(),
+        }
+    }
+
+    /// Run `f` inside the remembered span, wherever we are now.
+    pub(crate) fn in_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        #[cfg(feature = "tracing")]
+        return self.span.in_scope(f);
+
+        #[cfg(not(feature = "tracing"))]
+        f()
+    }
+}
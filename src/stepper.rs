@@ -0,0 +1,109 @@
+//! Spliterators from stepping and splitting functions.
+
+use crate::Spliterator;
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+/// A [Spliterator] driven by a pair of functions over some state.
+///
+/// Created by [`from_stepper()`].
+pub struct Stepper<S, F, G> {
+    /// The current state.
+    state: S,
+    /// The stepping and splitting functions, shared by every split.
+    fns: Arc<(F, G)>,
+}
+
+/// Create a [Spliterator] from explicit state, and functions to step and split it.
+///
+/// Like [`std::iter::from_fn()`], except that the state is separate from the closures, so that
+/// it can be split: `step` advances the state and returns the next item (or `None` when it's
+/// done), and `split` carves off part of the remaining work as a new state, if possible.  The
+/// state acts as a checkpoint of a resumable computation.
+pub fn from_stepper<S, T, F, G>(state: S, step: F, split: G) -> Stepper<S, F, G>
+where
+    F: Fn(&mut S) -> Option<T>,
+    G: Fn(&mut S) -> Option<S>,
+{
+    Stepper {
+        state,
+        fns: Arc::new((step, split)),
+    }
+}
+
+impl<S: Debug, F, G> Debug for Stepper<S, F, G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stepper")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, T, F, G> Iterator for Stepper<S, F, G>
+where
+    F: Fn(&mut S) -> Option<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.fns.0)(&mut self.state)
+    }
+}
+
+impl<S, T, F, G> Spliterator for Stepper<S, F, G>
+where
+    F: Fn(&mut S) -> Option<T>,
+    G: Fn(&mut S) -> Option<S>,
+{
+    fn split(&mut self) -> Option<Self> {
+        (self.fns.1)(&mut self.state).map(|state| Self {
+            state,
+            fns: self.fns.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    /// The numbers below 2^20 that reach 1 under the Collatz map, by searching backwards.
+    fn collatz() -> impl Spliterator<Item = u32> + Send {
+        from_stepper(
+            vec![1],
+            |stack: &mut Vec<u32>| {
+                let n = stack.pop()?;
+                if n < 1 << 19 {
+                    stack.push(2 * n);
+                }
+                if n > 4 && n % 6 == 4 {
+                    stack.push((n - 1) / 3);
+                }
+                Some(n)
+            },
+            |stack: &mut Vec<u32>| {
+                let len = stack.len();
+                (len >= 2).then(|| stack.split_off(len / 2))
+            },
+        )
+    }
+
+    #[test]
+    fn test_collatz_stepper() {
+        let mut expected: Vec<_> = collatz().collect();
+        expected.sort_unstable();
+        expected.dedup();
+
+        let mut actual: Vec<_> = collatz().par_split().collect();
+        actual.sort_unstable();
+
+        // Every number is reached exactly once
+        assert_eq!(actual, expected);
+        assert!(actual.contains(&27));
+    }
+}
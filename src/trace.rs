@@ -0,0 +1,164 @@
+//! Structured event logs of the bridge.
+
+#[cfg(feature = "trace")]
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// The kind of a [TraceEvent].
+#[cfg(feature = "trace")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TraceKind {
+    /// The branch split itself in two.
+    Split,
+    /// The branch was stolen, so its split budget was reset.
+    StealReset,
+    /// The branch consumed a run of items between split attempts.
+    Consume(usize),
+    /// The branch ran out of items (or the consumer was full).
+    Complete,
+}
+
+/// An event recorded by [ParSpliter::debug_trace()](crate::ParSpliter::debug_trace).
+#[cfg(feature = "trace")]
+#[derive(Clone, Copy, Debug)]
+pub struct TraceEvent {
+    /// The time since the start of the run.
+    pub time: Duration,
+    /// The depth of the branch in the split tree.
+    pub depth: usize,
+    /// The index of the worker thread, if any.
+    pub thread: Option<usize>,
+    /// What happened.
+    pub kind: TraceKind,
+}
+
+/// An in-progress traced run.
+#[cfg(feature = "trace")]
+struct Run {
+    id: u64,
+    start: Instant,
+    events: Vec<TraceEvent>,
+}
+
+/// The events of every traced run in progress.
+#[cfg(feature = "trace")]
+static RUNS: Mutex<Vec<Run>> = Mutex::new(Vec::new());
+
+/// The ID of the next traced run.
+#[cfg(feature = "trace")]
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Start recording a new run, returning its ID.
+#[cfg(feature = "trace")]
+pub(crate) fn begin() -> u64 {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    RUNS.lock().unwrap().push(Run {
+        id,
+        start: Instant::now(),
+        events: Vec::new(),
+    });
+    id
+}
+
+/// Stop recording a run, returning its events in time order.
+#[cfg(feature = "trace")]
+pub(crate) fn end(id: u64) -> Vec<TraceEvent> {
+    let mut runs = RUNS.lock().unwrap();
+    let i = runs.iter().position(|run| run.id == id).unwrap();
+    let mut events = runs.swap_remove(i).events;
+    events.sort_by_key(|event| event.time);
+    events
+}
+
+/// Records the events of a single call to the bridge.
+///
+/// Without the `trace` feature, this does nothing and optimizes away entirely.
+#[derive(Debug)]
+pub(crate) struct Tracer {
+    #[cfg(feature = "trace")]
+    id: Option<u64>,
+    #[cfg(feature = "trace")]
+    depth: usize,
+    /// The number of items consumed since the last event.
+    #[cfg(feature = "trace")]
+    consumed: usize,
+}
+
+impl Tracer {
+    /// Create a tracer for a branch at the given depth.
+    #[allow(unused_variables)]
+    pub(crate) fn new(id: Option<u64>, depth: usize) -> Self {
+        Self {
+            #[cfg(feature = "trace")]
+            id,
+            #[cfg(feature = "trace")]
+            depth,
+            #[cfg(feature = "trace")]
+            consumed: 0,
+        }
+    }
+
+    /// Record an event.
+    #[cfg(feature = "trace")]
+    fn record(&mut self, kind: TraceKind) {
+        let Some(id) = self.id else {
+            return;
+        };
+
+        let now = Instant::now();
+        let thread = rayon::current_thread_index();
+        let mut runs = RUNS.lock().unwrap();
+        let run = runs.iter_mut().find(|run| run.id == id).unwrap();
+
+        let mut push = |kind| {
+            run.events.push(TraceEvent {
+                time: now - run.start,
+                depth: self.depth,
+                thread,
+                kind,
+            })
+        };
+
+        // Flush any pending items first, so the log shows what happened between events
+        if self.consumed > 0 && kind != TraceKind::StealReset {
+            push(TraceKind::Consume(self.consumed));
+            self.consumed = 0;
+        }
+        push(kind);
+    }
+
+    /// Note that the split budget was reset by a steal.
+    #[inline]
+    pub(crate) fn steal_reset(&mut self) {
+        #[cfg(feature = "trace")]
+        self.record(TraceKind::StealReset);
+    }
+
+    /// Note that an item was consumed.
+    #[inline]
+    pub(crate) fn consume(&mut self) {
+        #[cfg(feature = "trace")]
+        if self.id.is_some() {
+            self.consumed += 1;
+        }
+    }
+
+    /// Note that the branch split.
+    #[inline]
+    pub(crate) fn split(&mut self) {
+        #[cfg(feature = "trace")]
+        self.record(TraceKind::Split);
+    }
+
+    /// Note that the branch completed.
+    #[inline]
+    pub(crate) fn complete(&mut self) {
+        #[cfg(feature = "trace")]
+        self.record(TraceKind::Complete);
+    }
+}
@@ -0,0 +1,77 @@
+//! Generic depth-first tree search.
+
+use crate::{ParSpliter, ParallelSpliterator, Spliterator};
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+/// A [Spliterator] that searches a tree (or graph) depth-first, given a function for the
+/// children of each node.
+///
+/// Created by [`tree_search()`].
+pub struct TreeSearch<S, C> {
+    /// The nodes we have yet to visit.
+    stack: Vec<S>,
+    /// Gets the children of a node.
+    children: Arc<C>,
+}
+
+/// Search a tree in parallel, starting from `root`.
+///
+/// Yields `root` and all its descendants.  Each node's children are pushed onto a stack as soon
+/// as it's visited, and splits hand off half of the stack, just like the hand-written search in
+/// the examples.  There is no check for nodes that have already been visited, so for
+/// graphs with cycles, `children` must cut them off itself (e.g. by tracking the depth).
+pub fn tree_search<S, C, I>(root: S, children: C) -> ParSpliter<TreeSearch<S, C>>
+where
+    S: Send,
+    C: Fn(&S) -> I + Send + Sync,
+    I: IntoIterator<Item = S>,
+{
+    TreeSearch {
+        stack: vec![root],
+        children: Arc::new(children),
+    }
+    .par_split()
+}
+
+impl<S: Debug, C> Debug for TreeSearch<S, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TreeSearch")
+            .field("stack", &self.stack)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, C, I> Iterator for TreeSearch<S, C>
+where
+    C: Fn(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend((self.children)(&node));
+        Some(node)
+    }
+}
+
+impl<S, C, I> Spliterator for TreeSearch<S, C>
+where
+    C: Fn(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    fn split(&mut self) -> Option<Self> {
+        let len = self.stack.len();
+        if len >= 2 {
+            let stack = self.stack.split_off(len / 2);
+            Some(Self {
+                stack,
+                children: self.children.clone(),
+            })
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Splitting vectors.
+
+use crate::{ParSpliter, Spliterator};
+
+use std::ops::RangeBounds;
+
+/// A [Spliterator] over the owned items of a vector.
+#[derive(Clone, Debug)]
+pub struct VecSpliter<T> {
+    /// The remaining items, in reverse order so we can pop them cheaply.
+    rev: Vec<T>,
+}
+
+impl<T> VecSpliter<T> {
+    /// Create a spliterator that yields the items of `vec`, in order.
+    pub fn new(mut vec: Vec<T>) -> Self {
+        vec.reverse();
+        Self { rev: vec }
+    }
+}
+
+impl<T> From<Vec<T>> for VecSpliter<T> {
+    fn from(vec: Vec<T>) -> Self {
+        Self::new(vec)
+    }
+}
+
+/// Make any iterator splittable, by collecting all of its items up front.
+///
+/// The whole iterator is drained into a [Vec] before this returns, so it costs a full
+/// sequential pass and enough memory for every item.  That's only worth it when processing each
+/// item is much more expensive than producing it.
+pub fn buffer_all<I: Iterator>(iter: I) -> VecSpliter<I::Item> {
+    VecSpliter::new(iter.collect())
+}
+
+impl<T> Iterator for VecSpliter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rev.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.rev.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for VecSpliter<T> {}
+
+impl<T> Spliterator for VecSpliter<T> {
+    fn split(&mut self) -> Option<Self> {
+        let len = self.rev.len();
+        if len >= 2 {
+            // Keep the earlier half (the end of the reversed vector), and hand
+            // off the later half
+            let earlier = self.rev.split_off(len / 2);
+            let later = std::mem::replace(&mut self.rev, earlier);
+            Some(Self { rev: later })
+        } else {
+            None
+        }
+    }
+}
+
+/// The storage of a [VecDrainSpliter].
+#[derive(Debug)]
+enum Storage<'a, T> {
+    /// The original vector.
+    Borrowed(&'a mut Vec<T>),
+    /// A piece that was split off of it.
+    Owned(Vec<T>),
+}
+
+/// A [Spliterator] that drains a vector in place.
+///
+/// Items are popped off the back of the vector one at a time, so no extra allocation is needed to
+/// produce them.  Splitting does allocate a new vector for the half that is handed off.  Once the
+/// spliterator is dropped, the source vector is empty, even if not every item was consumed.
+#[derive(Debug)]
+pub struct VecDrainSpliter<'a, T> {
+    /// The items that are left.
+    storage: Storage<'a, T>,
+}
+
+impl<'a, T> VecDrainSpliter<'a, T> {
+    /// Create a spliterator that drains `vec`, starting from the back.
+    pub fn new(vec: &'a mut Vec<T>) -> Self {
+        Self {
+            storage: Storage::Borrowed(vec),
+        }
+    }
+
+    /// Get the remaining items.
+    fn vec(&mut self) -> &mut Vec<T> {
+        match &mut self.storage {
+            Storage::Borrowed(vec) => vec,
+            Storage::Owned(vec) => vec,
+        }
+    }
+}
+
+impl<'a, T> Iterator for VecDrainSpliter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.vec().pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match &self.storage {
+            Storage::Borrowed(vec) => vec.len(),
+            Storage::Owned(vec) => vec.len(),
+        };
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> Spliterator for VecDrainSpliter<'a, T> {
+    fn split(&mut self) -> Option<Self> {
+        let vec = self.vec();
+        let len = vec.len();
+        if len >= 2 {
+            Some(Self {
+                storage: Storage::Owned(vec.split_off(len / 2)),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T> Drop for VecDrainSpliter<'a, T> {
+    fn drop(&mut self) {
+        self.vec().clear();
+    }
+}
+
+/// Extension trait for draining a range of a [Vec] in parallel.
+pub trait ParallelSplitDrain<T> {
+    /// Remove the given range from the vector, and iterate over the removed items in parallel.
+    ///
+    /// The items are moved out of the vector up front, so the rest of the vector is left intact
+    /// as soon as this returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, like [`Vec::drain()`].
+    fn par_split_drain<R: RangeBounds<usize>>(&mut self, range: R) -> ParSpliter<VecSpliter<T>>;
+}
+
+impl<T> ParallelSplitDrain<T> for Vec<T> {
+    fn par_split_drain<R: RangeBounds<usize>>(&mut self, range: R) -> ParSpliter<VecSpliter<T>> {
+        let drained = self.drain(range).collect();
+        ParSpliter::new(VecSpliter::new(drained))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ParallelSpliterator;
+
+    use rayon::iter::ParallelIterator;
+
+    #[test]
+    fn test_buffer_all() {
+        assert_eq!(buffer_all(0..100).par_split().count(), 100);
+
+        let odd = buffer_all((0..100).filter(|n| n % 2 == 1));
+        assert_eq!(odd.par_split().sum::<i32>(), 2500);
+    }
+
+    #[test]
+    fn test_vec_spliter() {
+        let mut iter = VecSpliter::new((0..10).collect());
+        assert_eq!(iter.next(), Some(0));
+
+        let split = iter.split().unwrap();
+        assert_eq!(iter.collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+        assert_eq!(split.collect::<Vec<_>>(), [6, 7, 8, 9]);
+
+        let sum: u64 = VecSpliter::new((0..10_000).collect()).par_split().sum();
+        assert_eq!(sum, 10_000 * 9_999 / 2);
+    }
+
+    #[test]
+    fn test_vec_drain_spliter() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let counts: Vec<_> = (0..1000).map(|_| AtomicUsize::new(0)).collect();
+        let mut vec: Vec<usize> = (0..1000).collect();
+
+        VecDrainSpliter::new(&mut vec).par_split().for_each(|i| {
+            counts[i].fetch_add(1, Ordering::Relaxed);
+        });
+
+        assert!(vec.is_empty());
+        assert!(counts.iter().all(|c| c.load(Ordering::Relaxed) == 1));
+
+        let mut vec: Vec<usize> = (0..1000).collect();
+        let found = VecDrainSpliter::new(&mut vec)
+            .par_split()
+            .find_any(|&i| i == 500);
+        assert_eq!(found, Some(500));
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_par_split_drain() {
+        let mut vec: Vec<u32> = (0..1000).collect();
+
+        let mut drained: Vec<_> = vec.par_split_drain(100..900).collect();
+        drained.sort_unstable();
+        assert_eq!(drained, (100..900).collect::<Vec<_>>());
+
+        assert_eq!(vec, (0..100).chain(900..1000).collect::<Vec<_>>());
+
+        assert_eq!(vec.par_split_drain(50..50).count(), 0);
+        assert_eq!(vec.len(), 200);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_par_split_drain_out_of_bounds() {
+        let mut vec = vec![1, 2, 3];
+        vec.par_split_drain(2..4);
+    }
+}
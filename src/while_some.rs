@@ -0,0 +1,66 @@
+//! Per-branch truncation at the first `None`.
+
+use crate::Spliterator;
+
+/// A [Spliterator] that unwraps `Some` items until it sees a `None` *within its branch*.
+///
+/// Created by [`ParSpliter::while_some_branch()`](crate::ParSpliter::while_some_branch).
+#[derive(Clone, Debug)]
+pub struct WhileSomeBranch<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// Whether this branch has seen a `None`.
+    done: bool,
+}
+
+impl<T> WhileSomeBranch<T> {
+    pub(crate) fn new(iter: T) -> Self {
+        Self { iter, done: false }
+    }
+}
+
+impl<T, R> Iterator for WhileSomeBranch<T>
+where
+    T: Iterator<Item = Option<R>>,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let item = self.iter.next()?;
+        self.done = item.is_none();
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            (0, Some(0))
+        } else {
+            (0, self.iter.size_hint().1)
+        }
+    }
+}
+
+impl<T, R> Spliterator for WhileSomeBranch<T>
+where
+    T: Spliterator<Item = Option<R>>,
+{
+    fn split(&mut self) -> Option<Self> {
+        if self.done {
+            return None;
+        }
+
+        self.iter.split().map(Self::new)
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
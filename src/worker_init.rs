@@ -0,0 +1,109 @@
+//! Per-worker initialization.
+
+use crate::Spliterator;
+
+use std::cell::Cell;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// The ID of the next run.
+static NEXT_RUN: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// The last run that was initialized on this thread.
+    static LAST_RUN: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A [Spliterator] that runs an initializer the first time each thread touches it.
+///
+/// Created by [`ParSpliter::with_worker_init()`](crate::ParSpliter::with_worker_init).
+#[derive(Clone)]
+pub struct WorkerInit<T, F> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The initializer, shared by every split.
+    init: Arc<F>,
+    /// The ID of this run, once it starts.
+    run: Option<u64>,
+    /// Whether this branch has checked its thread yet.
+    checked: bool,
+}
+
+impl<T, F> WorkerInit<T, F> {
+    pub(crate) fn new(iter: T, init: F) -> Self {
+        Self {
+            iter,
+            init: Arc::new(init),
+            run: None,
+            checked: false,
+        }
+    }
+
+    /// Get the ID of this run, starting a new one if necessary.
+    ///
+    /// The ID is assigned by the first branch to do anything, rather than at construction, so
+    /// that clones that are run separately count as separate runs.
+    fn run(&mut self) -> u64 {
+        *self
+            .run
+            .get_or_insert_with(|| NEXT_RUN.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl<T: Debug, F> Debug for WorkerInit<T, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkerInit")
+            .field("iter", &self.iter)
+            .field("run", &self.run)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, F> Iterator for WorkerInit<T, F>
+where
+    T: Iterator,
+    F: Fn(),
+{
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.checked {
+            self.checked = true;
+            let run = self.run();
+            if LAST_RUN.replace(run) != run {
+                (self.init)();
+            }
+        }
+
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, F> Spliterator for WorkerInit<T, F>
+where
+    T: Spliterator,
+    F: Fn(),
+{
+    fn split(&mut self) -> Option<Self> {
+        let run = self.run();
+        self.iter.split().map(|iter| Self {
+            iter,
+            init: self.init.clone(),
+            run: Some(run),
+            checked: false,
+        })
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
@@ -0,0 +1,58 @@
+//! Cooperative yielding.
+
+use crate::Spliterator;
+
+use std::thread;
+
+/// A [Spliterator] that periodically yields its thread to the OS scheduler.
+///
+/// Created by [`ParSpliter::with_yield_every()`](crate::ParSpliter::with_yield_every).
+#[derive(Clone, Debug)]
+pub struct YieldEvery<T> {
+    /// The underlying Spliterator.
+    iter: T,
+    /// The number of items between yields, or zero to never yield.
+    n: usize,
+    /// The number of items produced since the last yield.
+    count: usize,
+}
+
+impl<T> YieldEvery<T> {
+    pub(crate) fn new(iter: T, n: usize) -> Self {
+        Self { iter, n, count: 0 }
+    }
+}
+
+impl<T: Iterator> Iterator for YieldEvery<T> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.n != 0 {
+            self.count += 1;
+            if self.count == self.n {
+                self.count = 0;
+                thread::yield_now();
+            }
+        }
+
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Spliterator> Spliterator for YieldEvery<T> {
+    fn split(&mut self) -> Option<Self> {
+        self.iter.split().map(|iter| Self::new(iter, self.n))
+    }
+
+    fn max_items_per_branch(&self) -> Option<usize> {
+        self.iter.max_items_per_branch()
+    }
+
+    fn should_split(&self) -> bool {
+        self.iter.should_split()
+    }
+}
@@ -0,0 +1,42 @@
+//! Searches of the pocket cube from the examples.
+
+// Not every test uses every helper
+#[allow(dead_code)]
+#[path = "../examples/util/mod.rs"]
+mod util;
+
+use util::cube::PocketCube;
+use util::dfs::{DepthFirstSearch, Node};
+
+use rayon::iter::ParallelIterator;
+
+/// How many moves from the end of the search to start, to keep the tests quick.
+const MOVES: u8 = 5;
+
+/// The root of the shortened search.
+fn root() -> Node {
+    Node::at_depth(PocketCube::solved(), PocketCube::GODS_NUMBER - MOVES)
+}
+
+/// The children of a node, in a form tree_search() can hold on to.
+fn children(node: &Node) -> Vec<Node> {
+    node.children().into_iter().collect()
+}
+
+#[test]
+fn test_tree_search() {
+    // Same number of nodes as the hand-written search
+    let count = spliter::tree_search(root(), children).count();
+    assert_eq!(count, DepthFirstSearch::from_node(root()).count());
+}
+
+#[test]
+fn test_max_depth_by() {
+    // The search goes all the way to God's number, without reaching the impossible cube
+    let impossible = PocketCube::impossible();
+    let depth = spliter::tree_search(root(), children).max_depth_by(|node| {
+        assert!(node.cube != impossible);
+        node.depth.into()
+    });
+    assert_eq!(depth, u32::from(PocketCube::GODS_NUMBER));
+}